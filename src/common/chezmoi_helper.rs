@@ -0,0 +1,200 @@
+use itertools::Itertools;
+
+const FILE_MODE: u32 = 0o644;
+const EXECUTABLE_MODE: u32 = 0o755;
+const GROUP_OTHER_MASK: u32 = 0o077;
+const WRITE_MASK: u32 = 0o222;
+
+const DOT_PREFIX: &str = "dot_";
+const LITERAL_PREFIX: &str = "literal_";
+const LITERAL_SUFFIX: &str = ".literal";
+const TEMPLATE_SUFFIX: &str = ".tmpl";
+
+const SKIPPED_PREFIXES: [&str; 4] = ["run_", "modify_", "remove_", "encrypted_"];
+const IGNORED_PREFIXES: [&str; 7] = [
+    "create_",
+    "exact_",
+    "external_",
+    "once_",
+    "onchange_",
+    "before_",
+    "after_",
+];
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChezmoiName {
+    pub name: String,
+    pub private: bool,
+    pub readonly: bool,
+    pub executable: bool,
+    pub empty: bool,
+    pub symlink: bool,
+    pub template: bool,
+    pub skipped: Vec<String>,
+    pub ignored: Vec<String>,
+}
+
+impl ChezmoiName {
+    pub fn file_mode(&self) -> u32 {
+        let mut mode = if self.executable {
+            EXECUTABLE_MODE
+        } else {
+            FILE_MODE
+        };
+        if self.private {
+            mode &= !GROUP_OTHER_MASK;
+        }
+        if self.readonly {
+            mode &= !WRITE_MASK;
+        }
+        mode
+    }
+}
+
+pub fn parse_name(source_name: &str) -> ChezmoiName {
+    let mut result = ChezmoiName::default();
+    let mut rest = source_name;
+
+    if let Some(stripped) = rest.strip_suffix(LITERAL_SUFFIX) {
+        rest = stripped;
+    } else if let Some(stripped) = rest.strip_suffix(TEMPLATE_SUFFIX) {
+        rest = stripped;
+        result.template = true;
+    }
+
+    loop {
+        if let Some(stripped) = rest.strip_prefix(LITERAL_PREFIX) {
+            rest = stripped;
+            break;
+        }
+        if let Some(stripped) = rest.strip_prefix(DOT_PREFIX) {
+            result.name = format!(".{}", stripped);
+            return result;
+        }
+        let Some((prefix, stripped)) = split_attribute(rest) else {
+            break;
+        };
+        match prefix {
+            "private_" => result.private = true,
+            "readonly_" => result.readonly = true,
+            "executable_" => result.executable = true,
+            "empty_" => result.empty = true,
+            "symlink_" => result.symlink = true,
+            _ if SKIPPED_PREFIXES.contains(&prefix) => result.skipped.push(prefix.to_string()),
+            _ => result.ignored.push(prefix.to_string()),
+        }
+        rest = stripped;
+    }
+    result.name = rest.to_string();
+    result
+}
+
+pub fn convert_template(content: &str) -> Result<String, Vec<String>> {
+    let mut result = String::with_capacity(content.len());
+    let mut unsupported = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        let expression = rest[start + 2..start + end]
+            .trim_matches('-')
+            .trim()
+            .to_string();
+        result.push_str(&rest[..start]);
+        match parse_env_expression(&expression) {
+            Some(name) => result.push_str(&format!("{{{{ {} }}}}", name)),
+            None => unsupported.push(expression),
+        }
+        rest = &rest[start + end + 2..];
+    }
+    result.push_str(rest);
+
+    if unsupported.is_empty() {
+        Ok(result)
+    } else {
+        Err(unsupported.into_iter().unique().collect())
+    }
+}
+
+fn split_attribute(name: &str) -> Option<(&str, &str)> {
+    ["private_", "readonly_", "executable_", "empty_", "symlink_"]
+        .into_iter()
+        .chain(SKIPPED_PREFIXES)
+        .chain(IGNORED_PREFIXES)
+        .find_map(|prefix| {
+            name.strip_prefix(prefix)
+                .map(|stripped| (&name[..prefix.len()], stripped))
+        })
+}
+
+fn parse_env_expression(expression: &str) -> Option<&str> {
+    let argument = expression.strip_prefix("env")?.trim();
+    let name = argument.strip_prefix('"')?.strip_suffix('"')?;
+    let is_valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|el| el.is_ascii_alphanumeric() || el == '_');
+    is_valid.then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_names_reads_attributes() {
+        let cases = [
+            ("dot_bashrc", ".bashrc", 0o644, false, false),
+            ("private_dot_ssh", ".ssh", 0o600, false, false),
+            ("executable_script.sh", "script.sh", 0o755, false, false),
+            ("private_executable_run.sh", "run.sh", 0o700, false, false),
+            ("readonly_dot_netrc", ".netrc", 0o444, false, false),
+            ("symlink_dot_vimrc", ".vimrc", 0o644, true, false),
+            ("dot_gitconfig.tmpl", ".gitconfig", 0o644, false, true),
+            ("literal_dot_keep.tmpl", "dot_keep", 0o644, false, true),
+            ("dot_notes.literal", ".notes", 0o644, false, false),
+        ];
+        for (source_name, name, mode, symlink, template) in cases {
+            let parsed = parse_name(source_name);
+            assert_eq!(parsed.name, name, "{}", source_name);
+            assert_eq!(parsed.file_mode(), mode, "{}", source_name);
+            assert_eq!(parsed.symlink, symlink, "{}", source_name);
+            assert_eq!(parsed.template, template, "{}", source_name);
+        }
+    }
+
+    #[test]
+    fn parsing_names_reports_unsupported_attributes() {
+        let parsed = parse_name("empty_dot_hushlogin");
+        assert!(parsed.empty);
+        assert_eq!(parsed.name, ".hushlogin");
+
+        let parsed = parse_name("run_once_install.sh");
+        assert_eq!(parsed.skipped, vec!["run_"]);
+
+        let parsed = parse_name("create_private_dot_config");
+        assert_eq!(parsed.ignored, vec!["create_"]);
+        assert!(parsed.private);
+    }
+
+    #[test]
+    fn converting_templates_keeps_env_lookups() {
+        let cases = [
+            ("plain", Ok("plain")),
+            ("user = {{ env \"USER\" }}", Ok("user = {{ USER }}")),
+            ("{{- env \"HOME\" -}}/bin", Ok("{{ HOME }}/bin")),
+            (
+                "{{ .chezmoi.os }} {{ env \"USER\" }} {{ .chezmoi.os }}",
+                Err(vec![".chezmoi.os"]),
+            ),
+            ("{{ env USER }}", Err(vec!["env USER"])),
+        ];
+        for (content, expected) in cases {
+            let expected = expected
+                .map(|el| el.to_string())
+                .map_err(|el| el.iter().map(|el| el.to_string()).collect_vec());
+            assert_eq!(convert_template(content), expected, "{}", content);
+        }
+    }
+}
@@ -1,2 +1,30 @@
 pub const BINDINGS_CONFIG_FILE: &str = ".bindings";
+pub const IGNORE_FILE: &str = ".configsignore";
+pub const SETTINGS_FILE: &str = ".configsrc";
+pub const HOOKS_DIR: &str = ".hooks";
 pub const DEFAULT_VERSION: &str = "default";
+pub const ROOT_NAMESPACE: &str = "root";
+pub const ESCALATION_TOOL_ENV: &str = "CONFIGS_ESCALATION";
+pub const ESCALATION_TOOLS: [&str; 2] = ["sudo", "doas"];
+pub const STATE_DIR: &str = "configs";
+pub const BACKUPS_DIR: &str = "backups";
+pub const BACKUP_OBJECTS_DIR: &str = "objects";
+pub const BACKUP_SNAPSHOTS_DIR: &str = "snapshots";
+pub const BACKUP_ARCHIVES_DIR: &str = "archives";
+pub const HISTORY_FILE: &str = "history.yml";
+pub const WATCH_DEBOUNCE_MILLIS: u64 = 300;
+pub const WATCH_REFRESH_MILLIS: u64 = 1000;
+pub const ENV_KEYS_VARIABLE: &str = "CONFIGS_ENV_KEYS";
+pub const SCOPE_FILE: &str = ".configs-select";
+pub const SCOPES_DIR: &str = "scopes";
+pub const SESSION_VARIABLE: &str = "CONFIGS_SESSION";
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+pub const BUNDLE_MANIFEST_FILE: &str = "manifest.yml";
+pub const BUNDLE_FILES_DIR: &str = "files";
+pub const BUNDLE_MODULE_HOOKS_DIR: &str = "hooks/module";
+pub const BUNDLE_CONFIG_HOOKS_DIR: &str = "hooks/config";
+pub const INSTALL_STAGING_DIR: &str = "install";
+pub const EJECTED_FILE: &str = "ejected.yml";
+pub const EDIT_STAGING_DIR: &str = "edit";
+pub const DEFAULT_EDITOR: &str = "vi";
+pub const INTERRUPTED_EXIT_CODE: i32 = 130;
@@ -0,0 +1,157 @@
+use std::{
+    fs::{self, File, FileTimes, Metadata},
+    os::unix::fs::{chown, MetadataExt, PermissionsExt},
+    path::Path,
+};
+
+use anyhow::Result;
+use symlink::symlink_auto;
+use walkdir::WalkDir;
+
+use crate::{common::ignore_helper::IgnoreRules, types::bindings::Owner};
+
+const MODE_MASK: u32 = 0o7777;
+
+pub fn copy_preserving(from: &Path, to: &Path, ignore_rules: &IgnoreRules) -> Result<()> {
+    copy_entry(from, to, Path::new(""), ignore_rules)
+}
+
+fn copy_entry(from: &Path, to: &Path, relative: &Path, ignore_rules: &IgnoreRules) -> Result<()> {
+    let metadata = fs::symlink_metadata(from)?;
+    if ignore_rules.is_ignored(relative, metadata.is_dir()) {
+        return Ok(());
+    }
+    if metadata.is_symlink() {
+        symlink_auto(fs::read_link(from)?, to)?;
+        return Ok(());
+    }
+
+    if metadata.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_entry(
+                &entry.path(),
+                &to.join(entry.file_name()),
+                &relative.join(entry.file_name()),
+                ignore_rules,
+            )?;
+        }
+    } else if !metadata.is_file() {
+        return Ok(());
+    } else {
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(from, to)?;
+    }
+    apply_metadata(to, &metadata)
+}
+
+pub fn is_copy_of(source: &Path, target: &Path, ignore_rules: &IgnoreRules) -> Result<bool> {
+    if source.is_symlink() {
+        return is_same_link(source, target);
+    }
+    if target.is_symlink() || !target.exists() {
+        return Ok(false);
+    }
+    if source.is_file() {
+        return Ok(target.is_file() && fs::read(source)? == fs::read(target)?);
+    }
+    let entries = WalkDir::new(source)
+        .min_depth(1)
+        .into_iter()
+        .filter_entry(|entry| {
+            let relative = entry.path().strip_prefix(source).unwrap_or(entry.path());
+            !ignore_rules.is_ignored(relative, entry.file_type().is_dir())
+        });
+    for entry in entries {
+        let entry = entry?;
+        let target_path = target.join(entry.path().strip_prefix(source)?);
+        let is_same = if entry.path_is_symlink() {
+            is_same_link(entry.path(), &target_path)?
+        } else if entry.file_type().is_file() {
+            target_path.is_file() && fs::read(entry.path())? == fs::read(&target_path)?
+        } else {
+            true
+        };
+        if !is_same {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn is_same_link(source: &Path, target: &Path) -> Result<bool> {
+    Ok(target.is_symlink() && fs::read_link(source)? == fs::read_link(target)?)
+}
+
+pub fn get_mode(path: &Path) -> Option<u32> {
+    fs::metadata(path)
+        .ok()
+        .map(|metadata| metadata.permissions().mode() & MODE_MASK)
+}
+
+pub fn get_owner(path: &Path) -> Option<Owner> {
+    fs::metadata(path).ok().map(|metadata| Owner {
+        uid: metadata.uid(),
+        gid: metadata.gid(),
+    })
+}
+
+pub fn set_mode(path: &Path, mode: u32) -> Result<()> {
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))?;
+    Ok(())
+}
+
+fn apply_metadata(path: &Path, metadata: &Metadata) -> Result<()> {
+    let times = FileTimes::new()
+        .set_accessed(metadata.accessed()?)
+        .set_modified(metadata.modified()?);
+    File::open(path)?.set_times(times)?;
+    // Only succeeds for root, regular users keep owning their copies.
+    let _ = chown(path, Some(metadata.uid()), Some(metadata.gid()));
+    fs::set_permissions(path, metadata.permissions())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::test_helper::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn compares_symlinks_by_target() {
+        let dir = TempDir::new("files-helper");
+        let source = dir.path().join("source");
+        let copy = dir.path().join("copy");
+        fs::create_dir_all(source.join("nested")).unwrap();
+        fs::write(source.join("nested/file"), "content").unwrap();
+        symlink_auto("nested/file", source.join("link")).unwrap();
+        copy_preserving(&source, &copy, &IgnoreRules::default()).unwrap();
+        assert!(is_copy_of(&source, &copy, &IgnoreRules::default()).unwrap());
+        assert!(is_copy_of(
+            &source.join("link"),
+            &copy.join("link"),
+            &IgnoreRules::default()
+        )
+        .unwrap());
+
+        fs::remove_file(copy.join("link")).unwrap();
+        symlink_auto("elsewhere", copy.join("link")).unwrap();
+        assert!(!is_copy_of(&source, &copy, &IgnoreRules::default()).unwrap());
+        assert!(!is_copy_of(
+            &source.join("link"),
+            &copy.join("link"),
+            &IgnoreRules::default()
+        )
+        .unwrap());
+        assert!(!is_copy_of(
+            &source.join("link"),
+            &copy.join("missing"),
+            &IgnoreRules::default()
+        )
+        .unwrap());
+    }
+}
@@ -0,0 +1,47 @@
+use std::path::Path;
+
+use anyhow::Result;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+use super::constants;
+
+#[derive(Clone, Debug)]
+pub struct IgnoreRules {
+    gitignore: Gitignore,
+}
+
+impl Default for IgnoreRules {
+    fn default() -> Self {
+        IgnoreRules {
+            gitignore: Gitignore::empty(),
+        }
+    }
+}
+
+impl IgnoreRules {
+    pub fn load(module_dir_path: &Path) -> Result<IgnoreRules> {
+        let ignore_path = module_dir_path.join(constants::IGNORE_FILE);
+        if !ignore_path.is_file() {
+            return Ok(IgnoreRules::default());
+        }
+        let mut builder = GitignoreBuilder::new(module_dir_path);
+        if let Some(error) = builder.add(&ignore_path) {
+            return Err(error.into());
+        }
+        Ok(IgnoreRules {
+            gitignore: builder.build()?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.gitignore.is_empty()
+    }
+
+    pub fn is_ignored(&self, relative_path: &Path, is_dir: bool) -> bool {
+        !relative_path.as_os_str().is_empty()
+            && self
+                .gitignore
+                .matched_path_or_any_parents(relative_path, is_dir)
+                .is_ignore()
+    }
+}
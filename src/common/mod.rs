@@ -1,4 +1,14 @@
+pub mod chezmoi_helper;
 pub mod colors_helper;
 pub mod constants;
+pub mod files_helper;
+pub mod ignore_helper;
+pub mod names_helper;
 pub mod paths_helper;
+pub mod privileges_helper;
+pub mod signals_helper;
+pub mod state_helper;
+pub mod template_helper;
+#[cfg(test)]
+pub mod test_helper;
 pub mod versions_helper;
@@ -0,0 +1,90 @@
+use anyhow::Result;
+use itertools::Itertools;
+
+use crate::types::errors::ConfigsError;
+
+const MAX_SUGGESTIONS: usize = 3;
+const SUGGESTION_SIMILARITY: f64 = 0.7;
+
+pub fn resolve_name(kind: &str, input: &str, candidates: &[String]) -> Result<String> {
+    resolve(kind, input, candidates, true)
+}
+
+pub fn resolve_name_strictly(kind: &str, input: &str, candidates: &[String]) -> Result<String> {
+    resolve(kind, input, candidates, false)
+}
+
+fn resolve(kind: &str, input: &str, candidates: &[String], allow_typos: bool) -> Result<String> {
+    if candidates.iter().any(|el| el == input) {
+        return Ok(input.to_string());
+    }
+
+    let prefixed: Vec<&String> = candidates
+        .iter()
+        .filter(|el| el.starts_with(input))
+        .collect();
+    let typo_distance = (input.chars().count() / 3).max(1);
+    let matches = if prefixed.is_empty() && allow_typos {
+        candidates
+            .iter()
+            .filter(|el| strsim::osa_distance(input, el) <= typo_distance)
+            .collect()
+    } else {
+        prefixed
+    };
+
+    match matches.as_slice() {
+        [name] => Ok(name.to_string()),
+        [] => Err(ConfigsError::NameNotFound(
+            kind.to_string(),
+            input.to_string(),
+            get_suggestions(input, candidates),
+        )
+        .into()),
+        _ => Err(ConfigsError::AmbiguousName(
+            kind.to_string(),
+            input.to_string(),
+            matches.into_iter().cloned().sorted().collect(),
+        )
+        .into()),
+    }
+}
+
+pub fn get_suggestions(input: &str, candidates: &[String]) -> Vec<String> {
+    candidates
+        .iter()
+        .map(|el| (strsim::jaro_winkler(input, el), el))
+        .filter(|(similarity, _)| *similarity >= SUGGESTION_SIMILARITY)
+        .sorted_by(|a, b| b.0.total_cmp(&a.0))
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates() -> Vec<String> {
+        ["nvim", "zsh", "zellij"].map(String::from).to_vec()
+    }
+
+    #[test]
+    fn resolves_exact_names_and_unique_prefixes() {
+        for resolve in [resolve_name, resolve_name_strictly] {
+            assert_eq!(resolve("module", "nvim", &candidates()).unwrap(), "nvim");
+            assert_eq!(resolve("module", "ze", &candidates()).unwrap(), "zellij");
+            assert!(resolve("module", "z", &candidates()).is_err());
+        }
+    }
+
+    #[test]
+    fn corrects_typos_unless_strict() {
+        assert_eq!(
+            resolve_name("module", "nvmi", &candidates()).unwrap(),
+            "nvim"
+        );
+        let error = resolve_name_strictly("module", "nvmi", &candidates()).unwrap_err();
+        assert!(error.to_string().contains("nvim"));
+    }
+}
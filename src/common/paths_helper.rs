@@ -1,34 +1,214 @@
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 
 use anyhow::Result;
 use commons::utils::path_util::get_home_dir_path;
 
+use crate::{common::constants::ROOT_NAMESPACE, types::errors::ConfigsError};
+
+const DOT_PREFIX: &str = "dot_";
+const LITERAL_PREFIX: &str = "literal_";
+
 pub fn get_path_suffix_in_home(path: &Path) -> Result<PathBuf> {
     let home_path = get_home_dir_path()?;
     let suffix = path.strip_prefix(&home_path)?;
     Ok(suffix.to_path_buf())
 }
 
+pub fn get_display_path(path: &Path) -> String {
+    match get_path_suffix_in_home(path) {
+        Ok(suffix) => Path::new("~").join(suffix).to_string_lossy().to_string(),
+        Err(_) => path.to_string_lossy().to_string(),
+    }
+}
+
+pub fn to_portable_path(path: &Path) -> PathBuf {
+    match get_path_suffix_in_home(path) {
+        Ok(suffix) => Path::new("~").join(suffix),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+pub fn from_portable_path(path: &Path) -> Result<PathBuf> {
+    match path.strip_prefix("~") {
+        Ok(suffix) => Ok(get_home_dir_path()?.join(suffix)),
+        Err(_) => Ok(path.to_path_buf()),
+    }
+}
+
+pub fn get_internal_path(external_path: &Path) -> Result<PathBuf> {
+    match get_path_suffix_in_home(external_path) {
+        Ok(suffix) => {
+            let internal_path = convert_to_internal_path(&suffix)?;
+            match internal_path.strip_prefix(ROOT_NAMESPACE) {
+                Ok(rest) => {
+                    Ok(PathBuf::from(format!("{}{}", LITERAL_PREFIX, ROOT_NAMESPACE)).join(rest))
+                }
+                Err(_) => Ok(internal_path),
+            }
+        }
+        Err(_) => {
+            let suffix = external_path.strip_prefix("/")?;
+            Ok(PathBuf::from(ROOT_NAMESPACE).join(convert_to_internal_path(suffix)?))
+        }
+    }
+}
+
+pub fn get_external_path(internal_path: &Path) -> Result<PathBuf> {
+    match internal_path.strip_prefix(ROOT_NAMESPACE) {
+        Ok(rest) => Ok(PathBuf::from("/").join(convert_to_external_path(rest)?)),
+        Err(_) => Ok(get_home_dir_path()?.join(convert_to_external_path(internal_path)?)),
+    }
+}
+
 pub fn convert_to_internal_path(path: &Path) -> Result<PathBuf> {
-    let mut iter = path.components();
-    let first_component = iter.next().unwrap().as_os_str().to_str().unwrap();
-    if let Some(prefix) = first_component.strip_prefix(".") {
-        let new_prefix = format!("dot_{}", prefix);
-        let new_suffix = path.strip_prefix(first_component)?;
-        Ok(PathBuf::from(new_prefix).join(new_suffix))
+    map_components(path, encode_component)
+}
+
+pub fn convert_to_external_path(path: &Path) -> Result<PathBuf> {
+    map_components(path, decode_component)
+}
+
+fn map_components(path: &Path, mapper: fn(&str) -> String) -> Result<PathBuf> {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => {
+                let name = name
+                    .to_str()
+                    .ok_or_else(|| ConfigsError::UnsupportedPath(path.to_path_buf()))?;
+                result.push(mapper(name));
+            }
+            other => result.push(other.as_os_str()),
+        }
+    }
+    Ok(result)
+}
+
+fn encode_component(name: &str) -> String {
+    if let Some(rest) = name.strip_prefix('.') {
+        format!("{}{}", DOT_PREFIX, rest)
+    } else if name.starts_with(DOT_PREFIX) || name.starts_with(LITERAL_PREFIX) {
+        format!("{}{}", LITERAL_PREFIX, name)
     } else {
-        Ok(path.to_path_buf())
+        name.to_string()
     }
 }
 
-pub fn convert_to_external_path(path: &Path) -> Result<PathBuf> {
-    let mut iter = path.components();
-    let first_component = iter.next().unwrap().as_os_str().to_str().unwrap();
-    if let Some(prefix) = first_component.strip_prefix("dot_") {
-        let new_prefix = format!(".{}", prefix);
-        let new_suffix = path.strip_prefix(first_component)?;
-        Ok(PathBuf::from(new_prefix).join(new_suffix))
+fn decode_component(name: &str) -> String {
+    if let Some(rest) = name.strip_prefix(LITERAL_PREFIX) {
+        rest.to_string()
+    } else if let Some(rest) = name.strip_prefix(DOT_PREFIX) {
+        format!(".{}", rest)
     } else {
-        Ok(path.to_path_buf())
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use itertools::Itertools;
+
+    use crate::common::test_helper::init_test_env;
+
+    use super::*;
+
+    const STEMS: [&str; 4] = ["", "x", "root", "dot_y"];
+    const PREFIXES: [&str; 7] = [
+        "",
+        ".",
+        "dot_",
+        "literal_",
+        "literal_dot_",
+        "dot_literal_",
+        "..",
+    ];
+
+    // Every combination of a prefix and a stem, which covers the escaping
+    // prefixes on their own, stacked and nested.
+    fn get_components() -> Vec<String> {
+        PREFIXES
+            .iter()
+            .cartesian_product(STEMS)
+            .map(|(prefix, stem)| format!("{}{}", prefix, stem))
+            .filter(|el| !el.is_empty() && el != "." && el != "..")
+            .unique()
+            .collect()
+    }
+
+    fn get_paths(depth: usize) -> Vec<PathBuf> {
+        let components = get_components();
+        (1..=depth)
+            .flat_map(|length| {
+                (0..length)
+                    .map(|_| components.iter())
+                    .multi_cartesian_product()
+                    .map(|el| el.into_iter().collect::<PathBuf>())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn converting_round_trips() {
+        for path in get_paths(3) {
+            let internal_path = convert_to_internal_path(&path).unwrap();
+            assert_eq!(convert_to_external_path(&internal_path).unwrap(), path);
+        }
+    }
+
+    #[test]
+    fn converting_is_injective() {
+        let paths = get_paths(2);
+        let internal_paths: Vec<PathBuf> = paths
+            .iter()
+            .map(|el| convert_to_internal_path(el).unwrap())
+            .unique()
+            .collect();
+        assert_eq!(internal_paths.len(), paths.len());
+    }
+
+    #[test]
+    fn converting_hides_no_component() {
+        for path in get_paths(3) {
+            let internal_path = convert_to_internal_path(&path).unwrap();
+            assert!(internal_path.components().all(|el| !el
+                .as_os_str()
+                .to_str()
+                .unwrap()
+                .starts_with('.')));
+        }
+    }
+
+    #[test]
+    fn converting_encodes_nested_components() {
+        assert_eq!(
+            convert_to_internal_path(Path::new(".config/.hidden/dot_foo/literal_bar")).unwrap(),
+            Path::new("dot_config/dot_hidden/literal_dot_foo/literal_literal_bar")
+        );
+    }
+
+    #[test]
+    fn internal_path_round_trips() {
+        let home_path = init_test_env();
+        for path in get_paths(2) {
+            for external_path in [home_path.join(&path), Path::new("/etc").join(&path)] {
+                let internal_path = get_internal_path(&external_path).unwrap();
+                assert_eq!(get_external_path(&internal_path).unwrap(), external_path);
+            }
+        }
+    }
+
+    #[test]
+    fn internal_path_escapes_root_in_home() {
+        let home_path = init_test_env();
+        assert_eq!(
+            get_internal_path(&home_path.join("root/.a")).unwrap(),
+            Path::new("literal_root/dot_a")
+        );
+        assert_eq!(
+            get_internal_path(Path::new("/root/.a")).unwrap(),
+            Path::new("root/root/dot_a")
+        );
     }
 }
@@ -0,0 +1,190 @@
+use std::{
+    env,
+    ffi::CString,
+    fs,
+    os::unix::{ffi::OsStrExt, fs::chown},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::Result;
+use symlink::{remove_symlink_auto, symlink_auto};
+use walkdir::WalkDir;
+
+use crate::{
+    common::{
+        constants,
+        files_helper::{copy_preserving, set_mode},
+        ignore_helper::IgnoreRules,
+    },
+    types::{bindings::Owner, errors::ConfigsError},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Privileges {
+    User,
+    Elevated(String),
+}
+
+impl Privileges {
+    pub fn new(privileged: bool) -> Result<Privileges> {
+        if privileged {
+            Ok(Privileges::Elevated(find_escalation_tool()?))
+        } else {
+            Ok(Privileges::User)
+        }
+    }
+
+    pub fn remove(&self, path: &Path) -> Result<()> {
+        match self {
+            Privileges::User => {
+                if path.is_symlink() {
+                    remove_symlink_auto(path)?;
+                } else if path.is_file() {
+                    fs::remove_file(path)?;
+                } else if path.is_dir() {
+                    fs::remove_dir_all(path)?;
+                }
+                Ok(())
+            }
+            Privileges::Elevated(tool) => run_elevated(tool, "rm", &["-rf", "--"], &[path]),
+        }
+    }
+
+    pub fn copy(&self, from: &Path, to: &Path, ignore_rules: &IgnoreRules) -> Result<()> {
+        match self {
+            Privileges::User => copy_preserving(from, to, ignore_rules),
+            Privileges::Elevated(tool) => {
+                if let Some(parent) = to.parent() {
+                    run_elevated(tool, "mkdir", &["-p", "--"], &[parent])?;
+                }
+                if ignore_rules.is_empty() || !from.is_dir() {
+                    return run_elevated(tool, "cp", &["-Rp", "--"], &[from, to]);
+                }
+                let entries = WalkDir::new(from).into_iter().filter_entry(|entry| {
+                    let relative = entry.path().strip_prefix(from).unwrap_or(entry.path());
+                    !ignore_rules.is_ignored(relative, entry.file_type().is_dir())
+                });
+                for entry in entries {
+                    let entry = entry?;
+                    let target = to.join(entry.path().strip_prefix(from)?);
+                    if entry.file_type().is_dir() {
+                        run_elevated(tool, "mkdir", &["-p", "--"], &[&target])?;
+                    } else {
+                        run_elevated(tool, "cp", &["-p", "--"], &[entry.path(), &target])?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    pub fn copy_to_user(&self, from: &Path, to: &Path, ignore_rules: &IgnoreRules) -> Result<()> {
+        match self {
+            Privileges::User => copy_preserving(from, to, ignore_rules),
+            Privileges::Elevated(tool) => {
+                self.copy(from, to, ignore_rules)?;
+                // SAFETY: getuid and getgid always succeed.
+                let owner = unsafe { format!("{}:{}", libc::getuid(), libc::getgid()) };
+                run_elevated(tool, "chown", &["-R", &owner, "--"], &[to])
+            }
+        }
+    }
+
+    pub fn symlink(&self, target: &Path, link: &Path) -> Result<()> {
+        match self {
+            Privileges::User => Ok(symlink_auto(target, link)?),
+            Privileges::Elevated(tool) => run_elevated(tool, "ln", &["-s", "--"], &[target, link]),
+        }
+    }
+
+    pub fn set_mode(&self, path: &Path, mode: u32) -> Result<()> {
+        match self {
+            Privileges::User => set_mode(path, mode),
+            Privileges::Elevated(tool) => {
+                let mode = format!("{:o}", mode);
+                run_elevated(tool, "chmod", &[&mode, "--"], &[path])
+            }
+        }
+    }
+
+    pub fn set_owner(&self, path: &Path, owner: &Owner) -> Result<()> {
+        match self {
+            Privileges::User => Ok(chown(path, Some(owner.uid), Some(owner.gid))?),
+            Privileges::Elevated(tool) => {
+                let owner = format!("{}:{}", owner.uid, owner.gid);
+                run_elevated(tool, "chown", &[&owner, "--"], &[path])
+            }
+        }
+    }
+
+    pub fn read(&self, path: &Path) -> Result<Vec<u8>> {
+        match self {
+            Privileges::User => Ok(fs::read(path)?),
+            Privileges::Elevated(tool) => {
+                let output = Command::new(tool).arg("cat").arg("--").arg(path).output()?;
+                if !output.status.success() {
+                    return Err(ConfigsError::PrivilegedOperationFailed(
+                        "cat".to_string(),
+                        vec![path.to_path_buf()],
+                    )
+                    .into());
+                }
+                Ok(output.stdout)
+            }
+        }
+    }
+
+    pub fn create_dir_all(&self, path: &Path) -> Result<()> {
+        match self {
+            Privileges::User => Ok(fs::create_dir_all(path)?),
+            Privileges::Elevated(tool) => run_elevated(tool, "mkdir", &["-p", "--"], &[path]),
+        }
+    }
+
+    pub fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        match self {
+            Privileges::User => Ok(fs::rename(from, to)?),
+            Privileges::Elevated(tool) => run_elevated(tool, "mv", &["--"], &[from, to]),
+        }
+    }
+}
+
+pub fn requires_privileges(path: &Path) -> bool {
+    let parent = path.parent().unwrap_or(path);
+    let existing_parent = parent.ancestors().find(|el| el.exists()).unwrap_or(parent);
+    !is_writable(existing_parent) || (path.is_dir() && !is_writable(path))
+}
+
+fn is_writable(path: &Path) -> bool {
+    let Ok(path) = CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+    // SAFETY: `path` is a valid NUL-terminated string for the whole call.
+    unsafe { libc::access(path.as_ptr(), libc::W_OK) == 0 }
+}
+
+fn find_escalation_tool() -> Result<String> {
+    if let Ok(tool) = env::var(constants::ESCALATION_TOOL_ENV) {
+        return Ok(tool);
+    }
+    let search_path = env::var_os("PATH").unwrap_or_default();
+    constants::ESCALATION_TOOLS
+        .iter()
+        .find(|tool| env::split_paths(&search_path).any(|dir| dir.join(tool).is_file()))
+        .map(|tool| tool.to_string())
+        .ok_or_else(|| ConfigsError::EscalationToolNotFound.into())
+}
+
+fn run_elevated(tool: &str, program: &str, flags: &[&str], paths: &[&Path]) -> Result<()> {
+    let status = Command::new(tool)
+        .arg(program)
+        .args(flags)
+        .args(paths)
+        .status()?;
+    if !status.success() {
+        let paths: Vec<PathBuf> = paths.iter().map(|el| el.to_path_buf()).collect();
+        return Err(ConfigsError::PrivilegedOperationFailed(program.to_string(), paths).into());
+    }
+    Ok(())
+}
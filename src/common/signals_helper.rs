@@ -0,0 +1,71 @@
+use std::{
+    process,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+use anyhow::Result;
+
+use super::constants;
+
+static INSTALLED: Mutex<bool> = Mutex::new(false);
+static HELD: AtomicUsize = AtomicUsize::new(0);
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+pub struct SignalGuard;
+
+impl SignalGuard {
+    pub fn new() -> Result<SignalGuard> {
+        let mut installed = INSTALLED.lock().unwrap();
+        if !*installed {
+            ctrlc::set_handler(|| {
+                if HELD.load(Ordering::SeqCst) == 0 {
+                    process::exit(constants::INTERRUPTED_EXIT_CODE);
+                }
+                INTERRUPTED.store(true, Ordering::SeqCst);
+            })?;
+            *installed = true;
+        }
+        HELD.fetch_add(1, Ordering::SeqCst);
+        Ok(SignalGuard)
+    }
+
+    pub fn interrupted(&self) -> bool {
+        INTERRUPTED.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for SignalGuard {
+    fn drop(&mut self) {
+        HELD.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use super::*;
+
+    #[test]
+    fn signals_are_recorded_while_held() {
+        let guard = SignalGuard::new().unwrap();
+        let nested = SignalGuard::new().unwrap();
+        drop(nested);
+        assert!(!guard.interrupted());
+
+        // SAFETY: raising a signal has no memory safety requirements.
+        unsafe { libc::raise(libc::SIGTERM) };
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !guard.interrupted() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        assert!(guard.interrupted());
+    }
+}
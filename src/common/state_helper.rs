@@ -0,0 +1,14 @@
+use std::{env, path::PathBuf};
+
+use anyhow::Result;
+use commons::utils::path_util::get_home_dir_path;
+
+use super::constants;
+
+pub fn get_state_dir() -> Result<PathBuf> {
+    let state_home = match env::var_os("XDG_STATE_HOME") {
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => get_home_dir_path()?.join(".local").join("state"),
+    };
+    Ok(state_home.join(constants::STATE_DIR))
+}
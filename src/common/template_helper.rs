@@ -0,0 +1,63 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::Result;
+
+use crate::types::errors::ConfigsError;
+
+const ESCAPE: &str = "\\";
+
+pub fn render_file(path: &Path, variables: &HashMap<String, String>) -> Result<String> {
+    if !path.is_file() {
+        return Err(ConfigsError::NotAFile(path.to_path_buf()).into());
+    }
+    render_template(&fs::read_to_string(path)?, variables)
+}
+
+pub fn render_template(content: &str, variables: &HashMap<String, String>) -> Result<String> {
+    let mut result = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("{{") {
+        if rest[..start].ends_with(ESCAPE) {
+            result.push_str(&rest[..start - ESCAPE.len()]);
+            result.push_str("{{");
+            rest = &rest[start + 2..];
+            continue;
+        }
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + end].trim();
+        let value = variables
+            .get(name)
+            .ok_or_else(|| ConfigsError::UnknownTemplateVariable(name.to_string()))?;
+        result.push_str(&rest[..start]);
+        result.push_str(value);
+        rest = &rest[start + end + 2..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rendering_replaces_placeholders() {
+        let variables = HashMap::from([("NAME".to_string(), "value".to_string())]);
+        assert_eq!(
+            render_template("a {{ NAME }} b {{NAME}}", &variables).unwrap(),
+            "a value b value"
+        );
+        assert!(render_template("{{ OTHER }}", &variables).is_err());
+    }
+
+    #[test]
+    fn rendering_keeps_escaped_braces() {
+        let variables = HashMap::from([("NAME".to_string(), "value".to_string())]);
+        assert_eq!(
+            render_template("\\{{ user }} {{ NAME }} \\{{", &variables).unwrap(),
+            "{{ user }} value {{"
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Once,
+    },
+};
+
+static INIT: Once = Once::new();
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn get_tests_root() -> PathBuf {
+    env::temp_dir().join(format!("configs-tests-{}", std::process::id()))
+}
+
+pub fn init_test_env() -> PathBuf {
+    let home_path = get_tests_root().join("home");
+    INIT.call_once(|| {
+        fs::create_dir_all(&home_path).unwrap();
+        env::set_var("HOME", &home_path);
+        env::set_var("XDG_STATE_HOME", get_tests_root().join("state"));
+    });
+    home_path
+}
+
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    pub fn new(name: &str) -> TempDir {
+        init_test_env();
+        let path = get_tests_root().join(format!(
+            "{}-{}",
+            name,
+            COUNTER.fetch_add(1, Ordering::SeqCst)
+        ));
+        fs::create_dir_all(&path).unwrap();
+        TempDir { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
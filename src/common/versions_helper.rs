@@ -1,6 +1,11 @@
 use std::path::PathBuf;
 
-use versions::{Module, Version};
+use anyhow::Result;
+use versions::{Module, Repository, Version};
+
+use crate::types::errors::ConfigsError;
+
+use super::names_helper::{get_suggestions, resolve_name, resolve_name_strictly};
 
 pub fn get_module_path(version: &Version) -> PathBuf {
     version
@@ -9,11 +14,54 @@ pub fn get_module_path(version: &Version) -> PathBuf {
         .join(&version.module.module_dir)
 }
 
-pub fn get_version_from_name(name: &str, module: &Module) -> Version {
+pub fn get_version_from_name(name: &str, module: &Module) -> Result<Version> {
+    match module.versions.iter().find(|el| el.name == name) {
+        Some(version) => Ok(version.to_owned()),
+        None => Err(ConfigsError::NameNotFound(
+            "config".to_string(),
+            name.to_string(),
+            get_suggestions(name, &get_version_names(module)),
+        )
+        .into()),
+    }
+}
+
+pub fn find_module(repository: &Repository, name: &str) -> Result<Module> {
+    let names: Vec<String> = repository
+        .list_modules()?
+        .into_iter()
+        .map(|el| el.name)
+        .collect();
+    repository.get_module(&resolve_name("module", name, &names)?)
+}
+
+pub fn find_version(module: &Module, name: &str) -> Result<Version> {
+    get_version_from_name(
+        &resolve_name("config", name, &get_version_names(module))?,
+        module,
+    )
+}
+
+pub fn find_module_strictly(repository: &Repository, name: &str) -> Result<Module> {
+    let names: Vec<String> = repository
+        .list_modules()?
+        .into_iter()
+        .map(|el| el.name)
+        .collect();
+    repository.get_module(&resolve_name_strictly("module", name, &names)?)
+}
+
+pub fn find_version_strictly(module: &Module, name: &str) -> Result<Version> {
+    get_version_from_name(
+        &resolve_name_strictly("config", name, &get_version_names(module))?,
+        module,
+    )
+}
+
+fn get_version_names(module: &Module) -> Vec<String> {
     module
         .versions
         .iter()
-        .find(|el| el.name == name)
-        .unwrap()
-        .to_owned()
+        .map(|el| el.name.to_string())
+        .collect()
 }
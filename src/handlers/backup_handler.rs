@@ -0,0 +1,308 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    io::ErrorKind,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use chrono::Local;
+use commons::utils::file_util::{read_file, write_file};
+use sha2::{Digest, Sha256};
+use walkdir::WalkDir;
+
+use crate::{
+    common::{
+        constants,
+        ignore_helper::IgnoreRules,
+        privileges_helper::{requires_privileges, Privileges},
+        state_helper::get_state_dir,
+    },
+    types::{
+        backups::{Snapshot, SnapshotEntry},
+        errors::ConfigsError,
+    },
+};
+
+pub fn create_snapshot(operation: &str, paths: &[PathBuf]) -> Result<Option<Snapshot>> {
+    let mut entries = Vec::new();
+    for path in paths {
+        if !path.exists() && !path.is_symlink() {
+            continue;
+        }
+        for entry in WalkDir::new(path) {
+            entries.push(store_entry(entry?.path())?);
+        }
+    }
+    if entries.is_empty() {
+        return Ok(None);
+    }
+
+    let now = Local::now();
+    let manifest_hash = hash_content(serde_yml::to_string(&entries)?.as_bytes());
+    let snapshot = Snapshot {
+        id: format!("{}-{}", now.format("%Y%m%d-%H%M%S"), &manifest_hash[..8]),
+        created_at: now.to_rfc3339(),
+        operation: operation.to_string(),
+        entries,
+    };
+    let snapshots_dir = get_snapshots_dir()?;
+    fs::create_dir_all(&snapshots_dir)?;
+    // Written aside first, so listing never sees a partial manifest.
+    let partial_path = snapshots_dir.join(format!("{}.yml.partial", snapshot.id));
+    write_file(&partial_path, &serde_yml::to_string(&snapshot)?)?;
+    fs::rename(
+        &partial_path,
+        snapshots_dir.join(format!("{}.yml", snapshot.id)),
+    )?;
+    Ok(Some(snapshot))
+}
+
+pub fn list_snapshots() -> Result<Vec<Snapshot>> {
+    let snapshots_dir = get_snapshots_dir()?;
+    if !snapshots_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut snapshots = Vec::new();
+    for entry in fs::read_dir(snapshots_dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|el| el != "yml") {
+            continue;
+        }
+        let content = read_file(&path)?;
+        snapshots.push(serde_yml::from_str::<Snapshot>(&content)?);
+    }
+    snapshots.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(snapshots)
+}
+
+pub fn read_snapshot(id: &str) -> Result<Snapshot> {
+    if id.is_empty() || id.contains(['/', '\\']) || id.contains("..") {
+        return Err(ConfigsError::InvalidBackupId(id.to_string()).into());
+    }
+    let snapshot_path = get_snapshots_dir()?.join(format!("{}.yml", id));
+    if !snapshot_path.is_file() {
+        return Err(ConfigsError::BackupNotFound(id.to_string()).into());
+    }
+    Ok(serde_yml::from_str(&read_file(&snapshot_path)?)?)
+}
+
+pub fn restore_snapshot(id: &str) -> Result<Snapshot> {
+    let snapshot = read_snapshot(id)?;
+    let roots: Vec<PathBuf> = snapshot
+        .entries
+        .iter()
+        .filter(|entry| {
+            !snapshot
+                .entries
+                .iter()
+                .any(|other| other.path != entry.path && entry.path.starts_with(&other.path))
+        })
+        .map(|entry| entry.path.to_path_buf())
+        .collect();
+    create_snapshot(&format!("restore {}", id), &roots)?;
+
+    for entry in &snapshot.entries {
+        restore_entry(entry)?;
+    }
+    // Directory modes last, so read-only directories can still be filled.
+    for entry in snapshot.entries.iter().rev() {
+        if entry.hash.is_none() && entry.link_target.is_none() {
+            Privileges::new(requires_privileges(&entry.path))?.set_mode(&entry.path, entry.mode)?;
+        }
+    }
+    Ok(snapshot)
+}
+
+pub fn verify_snapshot(snapshot: &Snapshot) -> Result<()> {
+    for entry in &snapshot.entries {
+        let is_intact = match (&entry.hash, &entry.link_target) {
+            (Some(hash), _) => {
+                let object_path = get_objects_dir()?.join(hash);
+                fs::read(&object_path).is_ok_and(|content| &hash_content(&content) == hash)
+                    && fs::read(&entry.path).is_ok_and(|content| &hash_content(&content) == hash)
+            }
+            (None, Some(link_target)) => {
+                fs::read_link(&entry.path).is_ok_and(|target| &target == link_target)
+            }
+            (None, None) => is_directory_intact(snapshot, &entry.path),
+        };
+        if !is_intact {
+            return Err(ConfigsError::BackupVerificationFailed(entry.path.to_path_buf()).into());
+        }
+    }
+    Ok(())
+}
+
+fn is_directory_intact(snapshot: &Snapshot, path: &Path) -> bool {
+    if !fs::symlink_metadata(path).is_ok_and(|el| el.is_dir()) {
+        return false;
+    }
+    let recorded: BTreeSet<PathBuf> = snapshot
+        .entries
+        .iter()
+        .filter(|el| el.path.parent() == Some(path))
+        .map(|el| el.path.to_path_buf())
+        .collect();
+    fs::read_dir(path)
+        .and_then(|children| children.map(|el| Ok(el?.path())).collect::<Result<_, _>>())
+        .is_ok_and(|children: BTreeSet<PathBuf>| children == recorded)
+}
+
+fn store_entry(path: &Path) -> Result<SnapshotEntry> {
+    let metadata = fs::symlink_metadata(path)?;
+    let mut entry = SnapshotEntry {
+        path: path.to_path_buf(),
+        mode: metadata.permissions().mode() & 0o7777,
+        ..Default::default()
+    };
+    if metadata.is_symlink() {
+        entry.link_target = Some(fs::read_link(path)?);
+    } else if metadata.is_file() {
+        let content = match fs::read(path) {
+            Err(error) if error.kind() == ErrorKind::PermissionDenied => {
+                Privileges::new(true)?.read(path)?
+            }
+            result => result?,
+        };
+        let hash = hash_content(&content);
+        let object_path = get_objects_dir()?.join(&hash);
+        if !object_path.exists() {
+            fs::create_dir_all(get_objects_dir()?)?;
+            fs::write(&object_path, &content)?;
+        }
+        entry.hash = Some(hash);
+    }
+    Ok(entry)
+}
+
+fn restore_entry(entry: &SnapshotEntry) -> Result<()> {
+    let path = &entry.path;
+    let privileges = Privileges::new(requires_privileges(path))?;
+    let is_dir_entry = entry.hash.is_none() && entry.link_target.is_none();
+    let is_occupied = path.exists() || path.is_symlink();
+    if is_occupied && !(is_dir_entry && path.is_dir() && !path.is_symlink()) {
+        privileges.remove(path)?;
+    }
+    if let Some(parent) = path.parent() {
+        privileges.create_dir_all(parent)?;
+    }
+
+    if let Some(link_target) = &entry.link_target {
+        privileges.symlink(link_target, path)?;
+    } else if let Some(hash) = &entry.hash {
+        privileges.copy(
+            &get_objects_dir()?.join(hash),
+            path,
+            &IgnoreRules::default(),
+        )?;
+        privileges.set_mode(path, entry.mode)?;
+    } else {
+        privileges.create_dir_all(path)?;
+    }
+    Ok(())
+}
+
+fn hash_content(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+fn get_objects_dir() -> Result<PathBuf> {
+    Ok(get_state_dir()?
+        .join(constants::BACKUPS_DIR)
+        .join(constants::BACKUP_OBJECTS_DIR))
+}
+
+fn get_snapshots_dir() -> Result<PathBuf> {
+    Ok(get_state_dir()?
+        .join(constants::BACKUPS_DIR)
+        .join(constants::BACKUP_SNAPSHOTS_DIR))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, os::unix::fs::symlink};
+
+    use crate::common::test_helper::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn restoring_brings_back_files_links_and_directories() {
+        let temp_dir = TempDir::new("restore");
+        let root = temp_dir.path().join("root");
+        fs::create_dir_all(root.join("dir")).unwrap();
+        fs::write(root.join("dir/file"), "content").unwrap();
+        symlink("dir/file", root.join("link")).unwrap();
+        let snapshot = create_snapshot("test", &[root.to_path_buf()])
+            .unwrap()
+            .unwrap();
+
+        fs::remove_dir_all(&root).unwrap();
+        fs::create_dir_all(root.join("link")).unwrap();
+        fs::create_dir_all(root.join("dir/file")).unwrap();
+        restore_snapshot(&snapshot.id).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(root.join("dir/file")).unwrap(),
+            "content"
+        );
+        assert_eq!(
+            fs::read_link(root.join("link")).unwrap(),
+            PathBuf::from("dir/file")
+        );
+    }
+
+    #[test]
+    fn restoring_keeps_a_backup_of_the_replaced_content() {
+        let temp_dir = TempDir::new("restore-replaced");
+        let file = temp_dir.path().join("file");
+        fs::write(&file, "old").unwrap();
+        let snapshot = create_snapshot("test", &[file.to_path_buf()])
+            .unwrap()
+            .unwrap();
+
+        fs::write(&file, "new").unwrap();
+        restore_snapshot(&snapshot.id).unwrap();
+
+        assert_eq!(fs::read_to_string(&file).unwrap(), "old");
+        let replaced = list_snapshots()
+            .unwrap()
+            .into_iter()
+            .find(|el| el.operation == format!("restore {}", snapshot.id))
+            .unwrap();
+        assert_eq!(replaced.entries[0].path, file);
+    }
+
+    #[test]
+    fn verifying_checks_the_content_of_directories() {
+        let temp_dir = TempDir::new("verify");
+        let root = temp_dir.path().join("root");
+        fs::create_dir_all(root.join("dir")).unwrap();
+        fs::write(root.join("dir/file"), "content").unwrap();
+        let snapshot = create_snapshot("test", &[root.to_path_buf()])
+            .unwrap()
+            .unwrap();
+        verify_snapshot(&snapshot).unwrap();
+
+        fs::write(root.join("dir/unrecorded"), "content").unwrap();
+        assert!(verify_snapshot(&snapshot).is_err());
+        fs::remove_file(root.join("dir/unrecorded")).unwrap();
+
+        fs::remove_file(root.join("dir/file")).unwrap();
+        fs::create_dir(root.join("dir/file")).unwrap();
+        assert!(verify_snapshot(&snapshot).is_err());
+    }
+
+    #[test]
+    fn reading_rejects_ids_outside_the_store() {
+        for id in ["", "../history", "a/b", ".."] {
+            let error = read_snapshot(id).unwrap_err();
+            assert!(matches!(
+                error.downcast_ref::<ConfigsError>(),
+                Some(ConfigsError::InvalidBackupId(_))
+            ));
+        }
+    }
+}
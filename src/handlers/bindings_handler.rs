@@ -1,20 +1,29 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use commons::{
     traits::collections::ContainsPredicate,
     utils::file_util::{read_file, write_file},
 };
+use globset::GlobBuilder;
+use itertools::Itertools;
 use versions::{Module, Version};
 
 use crate::{
     common::{
         constants,
-        paths_helper::{convert_to_internal_path, get_path_suffix_in_home},
+        files_helper::{get_mode, get_owner},
+        paths_helper::{convert_to_external_path, get_internal_path},
+        privileges_helper::requires_privileges,
+    },
+    types::{
+        bindings::{Binding, Bindings, Deployment, ModuleBindings, PathOwner, VersionBindings},
+        errors::ConfigsError,
     },
-    types::bindings::{Binding, Bindings, ModuleBindings, VersionBindings},
 };
 
+use super::repository_handler::get_current_repository;
+
 pub fn is_path_bound(version: &Version, path: &Path) -> Result<bool> {
     let version_bindings = read_version_bindings(version)?;
     let contains = version_bindings
@@ -23,24 +32,69 @@ pub fn is_path_bound(version: &Version, path: &Path) -> Result<bool> {
     Ok(contains)
 }
 
-pub fn bind_path(version: &Version, path: &Path) -> Result<Binding> {
-    let mut suffixed_path = match get_path_suffix_in_home(path) {
-        Ok(path_suffix) => path_suffix,
-        Err(_) => path.to_path_buf(),
-    };
-    suffixed_path = convert_to_internal_path(&suffixed_path)?;
+pub fn bind_path(
+    version: &Version,
+    path: &Path,
+    pattern: &Option<String>,
+    deployment: Deployment,
+    validator: &Option<String>,
+) -> Result<Binding> {
+    if let Some(pattern) = pattern {
+        if !path.is_dir() {
+            return Err(ConfigsError::NotADirectory(path.to_path_buf()).into());
+        }
+        if GlobBuilder::new(pattern).build().is_err() {
+            return Err(ConfigsError::InvalidPattern(pattern.to_string()).into());
+        }
+    }
+    // Bindings keep the internal path they were created with, so entries from
+    // the older first-component-only encoding still resolve. A new path may
+    // encode to one of those though, which must not be shared.
+    let internal_path = get_internal_path(path)?;
+    let is_internal_path_taken = read_version_bindings(version)?.entries.contains(|el| {
+        el.internal_path.starts_with(&internal_path) || internal_path.starts_with(&el.internal_path)
+    });
+    if is_internal_path_taken {
+        return Err(ConfigsError::InternalPathTaken(internal_path).into());
+    }
+
+    let privileged = requires_privileges(path);
     let new_binding = Binding {
-        internal_path: suffixed_path.to_path_buf(),
+        internal_path,
         external_path: path.to_path_buf(),
+        privileged,
+        mode: if pattern.is_none() {
+            get_mode(path)
+        } else {
+            None
+        },
+        owner: if privileged { get_owner(path) } else { None },
+        pattern: pattern.to_owned(),
+        deployment,
+        validator: validator.to_owned(),
     };
     update_version_bindings(version, |version_binding| {
         let mut entries = version_binding.entries.to_owned();
         entries.push(new_binding.to_owned());
-        VersionBindings { entries }
+        VersionBindings {
+            entries,
+            ..version_binding.to_owned()
+        }
     })?;
     Ok(new_binding)
 }
 
+pub fn add_binding(version: &Version, binding: &Binding) -> Result<()> {
+    update_version_bindings(version, |version_binding| {
+        let mut entries = version_binding.entries.to_owned();
+        entries.push(binding.to_owned());
+        VersionBindings {
+            entries,
+            ..version_binding.to_owned()
+        }
+    })
+}
+
 pub fn unbind_path(version: &Version, path: &Path) -> Result<Binding> {
     let current_binding = read_version_bindings(version)?
         .entries
@@ -51,15 +105,16 @@ pub fn unbind_path(version: &Version, path: &Path) -> Result<Binding> {
     update_version_bindings(version, |version_binding| {
         let mut entries = version_binding.entries.to_owned();
         entries.retain(|binding| binding.external_path != path);
-        VersionBindings { entries }
+        VersionBindings {
+            entries,
+            ..version_binding.to_owned()
+        }
     })?;
     Ok(current_binding)
 }
 
 pub fn unbind_all(version: &Version) -> Result<()> {
-    update_version_bindings(version, |_| VersionBindings {
-        entries: Vec::new(),
-    })
+    update_version_bindings(version, |_| VersionBindings::default())
 }
 
 pub fn update_version_bindings(
@@ -91,6 +146,105 @@ pub fn update_version_bindings(
     Ok(())
 }
 
+struct ModuleLocation {
+    name: String,
+    module_path: PathBuf,
+    current_config: Option<String>,
+}
+
+pub fn find_path_owners(path: &Path) -> Result<Vec<PathOwner>> {
+    let repository = get_current_repository()?;
+    let modules = repository
+        .list_modules()?
+        .into_iter()
+        .map(|module| ModuleLocation {
+            module_path: repository.root_path.join(&module.directory),
+            current_config: module.current_version.map(|el| el.name),
+            name: module.name,
+        })
+        .collect_vec();
+    find_owners(&read_bindings(&repository.root_path)?, &modules, path)
+}
+
+fn find_owners(
+    bindings: &Bindings,
+    modules: &[ModuleLocation],
+    path: &Path,
+) -> Result<Vec<PathOwner>> {
+    let mut candidates = vec![path.to_path_buf()];
+    if let Ok(canonical_path) = path.canonicalize() {
+        if canonical_path != path {
+            candidates.push(canonical_path);
+        }
+    }
+
+    let mut owners = Vec::new();
+    for module in modules {
+        let Some(module_bindings) = bindings.module_bindings.get(&module.name) else {
+            continue;
+        };
+        for (config, version_bindings) in module_bindings
+            .version_bindings
+            .iter()
+            .sorted_by_key(|(name, _)| name.to_string())
+        {
+            let active = module.current_config.as_ref() == Some(config);
+            for binding in &version_bindings.entries {
+                let owns = candidates.iter().any(|candidate| {
+                    covers_path(binding, &binding.external_path, candidate, false)
+                        || (active
+                            && covers_path(
+                                binding,
+                                &module.module_path.join(&binding.internal_path),
+                                candidate,
+                                true,
+                            ))
+                });
+                if owns {
+                    owners.push(PathOwner {
+                        module: module.name.to_string(),
+                        config: config.to_string(),
+                        binding: binding.to_owned(),
+                        active,
+                    });
+                }
+            }
+        }
+    }
+    if owners.is_empty() {
+        return Err(ConfigsError::PathNotBound(path.to_path_buf()).into());
+    }
+    Ok(owners)
+}
+
+fn covers_path(binding: &Binding, root: &Path, path: &Path, internal: bool) -> bool {
+    let Ok(relative) = path.strip_prefix(root) else {
+        return false;
+    };
+    let Some(pattern) = &binding.pattern else {
+        return true;
+    };
+    if relative.as_os_str().is_empty() {
+        return true;
+    }
+    let relative: PathBuf = if internal {
+        match convert_to_external_path(relative) {
+            Ok(relative) => relative,
+            Err(_) => return false,
+        }
+    } else {
+        relative.to_path_buf()
+    };
+    let Ok(glob) = GlobBuilder::new(pattern).literal_separator(true).build() else {
+        return false;
+    };
+    let matcher = glob.compile_matcher();
+    relative
+        .ancestors()
+        .filter(|el| !el.as_os_str().is_empty())
+        .any(|el| matcher.is_match(el))
+}
+
 pub fn read_bindings(repository_path: &Path) -> Result<Bindings> {
     let bindings_path = repository_path.join(constants::BINDINGS_CONFIG_FILE);
 
@@ -129,3 +283,141 @@ pub fn write_bindings(repository_path: &Path, bindings: &Bindings) -> Result<()>
     write_file(&bindings_path, &content)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs};
+
+    use symlink::symlink_auto;
+
+    use crate::common::test_helper::TempDir;
+
+    use super::*;
+
+    fn create_bindings(config: &str, entries: Vec<Binding>) -> Bindings {
+        Bindings {
+            module_bindings: HashMap::from([(
+                "shell".to_string(),
+                ModuleBindings {
+                    version_bindings: HashMap::from([(
+                        config.to_string(),
+                        VersionBindings {
+                            entries,
+                            ..Default::default()
+                        },
+                    )]),
+                },
+            )]),
+        }
+    }
+
+    fn create_module(module_path: &Path, current_config: Option<&str>) -> Vec<ModuleLocation> {
+        vec![ModuleLocation {
+            name: "shell".to_string(),
+            module_path: module_path.to_path_buf(),
+            current_config: current_config.map(|el| el.to_string()),
+        }]
+    }
+
+    fn find_configs(bindings: &Bindings, modules: &[ModuleLocation], path: &Path) -> Vec<String> {
+        find_owners(bindings, modules, path)
+            .unwrap()
+            .into_iter()
+            .map(|el| format!("{}/{}", el.module, el.config))
+            .collect()
+    }
+
+    #[test]
+    fn bindings_own_paths_below_their_external_path() {
+        let temp_dir = TempDir::new("owners-plain");
+        let home_path = temp_dir.path().join("home");
+        let bindings = create_bindings(
+            "default",
+            vec![Binding {
+                internal_path: PathBuf::from("nvim"),
+                external_path: home_path.join(".config/nvim"),
+                ..Default::default()
+            }],
+        );
+        let modules = create_module(&temp_dir.path().join("shell"), None);
+
+        assert_eq!(
+            find_configs(
+                &bindings,
+                &modules,
+                &home_path.join(".config/nvim/init.lua")
+            ),
+            vec!["shell/default"]
+        );
+        assert!(
+            !find_owners(&bindings, &modules, &home_path.join(".config/nvim")).unwrap()[0].active
+        );
+    }
+
+    #[test]
+    fn pattern_bindings_own_matching_paths_only() {
+        let temp_dir = TempDir::new("owners-pattern");
+        let home_path = temp_dir.path().join("home");
+        let bindings = create_bindings(
+            "default",
+            vec![Binding {
+                internal_path: PathBuf::from("config"),
+                external_path: home_path.join(".config"),
+                pattern: Some("*.toml".to_string()),
+                ..Default::default()
+            }],
+        );
+        let modules = create_module(&temp_dir.path().join("shell"), Some("default"));
+
+        assert_eq!(
+            find_configs(
+                &bindings,
+                &modules,
+                &home_path.join(".config/starship.toml")
+            ),
+            vec!["shell/default"]
+        );
+        let error =
+            find_owners(&bindings, &modules, &home_path.join(".config/app.json")).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ConfigsError>(),
+            Some(ConfigsError::PathNotBound(path)) if path == &home_path.join(".config/app.json")
+        ));
+    }
+
+    #[test]
+    fn links_into_the_active_module_resolve_to_their_binding() {
+        let temp_dir = TempDir::new("owners-links");
+        let root = temp_dir.path().canonicalize().unwrap();
+        let module_path = root.join("shell");
+        fs::create_dir_all(&module_path).unwrap();
+        fs::write(module_path.join("zshrc"), "").unwrap();
+        let link_path = root.join("zshrc-link");
+        symlink_auto(module_path.join("zshrc"), &link_path).unwrap();
+        let bindings = create_bindings(
+            "default",
+            vec![Binding {
+                internal_path: PathBuf::from("zshrc"),
+                external_path: root.join("home/.zshrc"),
+                ..Default::default()
+            }],
+        );
+
+        let active = create_module(&module_path, Some("default"));
+        assert_eq!(
+            find_configs(&bindings, &active, &link_path),
+            vec!["shell/default"]
+        );
+        assert_eq!(
+            find_configs(&bindings, &active, &module_path.join("zshrc")),
+            vec!["shell/default"]
+        );
+
+        let inactive = create_module(&module_path, Some("work"));
+        let error = find_owners(&bindings, &inactive, &link_path).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ConfigsError>(),
+            Some(ConfigsError::PathNotBound(_))
+        ));
+    }
+}
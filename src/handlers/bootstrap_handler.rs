@@ -0,0 +1,239 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use globset::GlobBuilder;
+use versions::{Module, Version, VersionsCli};
+use walkdir::WalkDir;
+
+use crate::{
+    common::{
+        constants,
+        names_helper::get_suggestions,
+        versions_helper::{get_module_path, get_version_from_name},
+    },
+    types::{
+        bindings::Binding,
+        bootstrap::{BootstrapEntry, BootstrapOutcome, BootstrapReport},
+        conflicts::ConflictPolicy,
+        errors::ConfigsError,
+    },
+};
+
+use super::{
+    backup_handler::{create_snapshot, verify_snapshot},
+    bindings_handler::read_version_bindings,
+    commands_handler::select_config,
+    eject_handler::is_ejected,
+    repository_handler::get_current_repository,
+    settings_handler::read_settings,
+    workspace_handler::find_conflicts,
+};
+
+pub fn bootstrap(profile: &Option<String>) -> Result<BootstrapReport> {
+    let mut report = BootstrapReport::default();
+    match VersionsCli::new().init() {
+        Ok(output) => report.initialized = Some(output),
+        Err(error) => {
+            if get_current_repository().is_err() {
+                return Err(error);
+            }
+        }
+    }
+
+    let repository = get_current_repository()?;
+    let settings = read_settings(&repository.root_path)?;
+    let profile_configs = match profile {
+        Some(name) => settings.profiles.get(name).cloned().ok_or_else(|| {
+            let names: Vec<String> = settings.profiles.keys().cloned().collect();
+            ConfigsError::NameNotFound(
+                "profile".to_string(),
+                name.to_string(),
+                get_suggestions(name, &names),
+            )
+        })?,
+        None => BTreeMap::new(),
+    };
+
+    let modules = repository.list_modules()?;
+    let module_names: Vec<String> = modules.iter().map(|el| el.name.to_string()).collect();
+    for module_name in profile_configs.keys() {
+        if !module_names.contains(module_name) {
+            return Err(ConfigsError::NameNotFound(
+                "module".to_string(),
+                module_name.to_string(),
+                get_suggestions(module_name, &module_names),
+            )
+            .into());
+        }
+    }
+
+    let mut plan = Vec::new();
+    for module in modules {
+        if is_ejected(&repository.root_path, &module.name)? {
+            report.entries.push(BootstrapEntry {
+                module: module.name,
+                config: None,
+                outcome: BootstrapOutcome::Ejected,
+            });
+            continue;
+        }
+        let version = match profile_configs.get(&module.name) {
+            Some(config) => Some(get_version_from_name(config, &module)?),
+            None => get_default_version(&module),
+        };
+        plan.push((module, version));
+    }
+
+    let mut conflicts = Vec::new();
+    for (module, version) in &plan {
+        if let Some(version) = version {
+            conflicts.extend(find_version_conflicts(module, version)?);
+        }
+    }
+    if !conflicts.is_empty() {
+        let snapshot = create_snapshot("bootstrap", &conflicts)?;
+        if let Some(snapshot) = &snapshot {
+            verify_snapshot(snapshot)?;
+        }
+        report.backup = snapshot;
+    }
+
+    for (module, version) in plan {
+        let Some(version) = version else {
+            report.entries.push(BootstrapEntry {
+                module: module.name,
+                config: None,
+                outcome: BootstrapOutcome::NoConfigs,
+            });
+            continue;
+        };
+        let outcome = match select_config(
+            &repository,
+            &module.name,
+            &version.name,
+            ConflictPolicy::Overwrite,
+        ) {
+            Ok(previous) => BootstrapOutcome::Selected {
+                previous: previous.map(|el| el.name),
+            },
+            Err(error) => BootstrapOutcome::Failed(error.to_string()),
+        };
+        report.entries.push(BootstrapEntry {
+            module: module.name,
+            config: Some(version.name),
+            outcome,
+        });
+    }
+    Ok(report)
+}
+
+fn get_default_version(module: &Module) -> Option<Version> {
+    module
+        .current_version
+        .to_owned()
+        .or_else(|| {
+            module
+                .versions
+                .iter()
+                .find(|el| el.name == constants::DEFAULT_VERSION)
+                .cloned()
+        })
+        .or_else(|| module.versions.first().cloned())
+}
+
+fn find_version_conflicts(module: &Module, version: &Version) -> Result<Vec<PathBuf>> {
+    let bindings = read_version_bindings(version)?.entries;
+    let module_path = get_module_path(version);
+    let is_current = module
+        .current_version
+        .as_ref()
+        .is_some_and(|el| el.name == version.name);
+    let mut conflicts = Vec::new();
+    for binding in &bindings {
+        if is_current {
+            conflicts.extend(find_conflicts(binding, &module_path)?);
+        } else {
+            conflicts.extend(find_occupied_paths(binding, &module_path)?);
+        }
+    }
+    Ok(conflicts)
+}
+
+fn find_occupied_paths(binding: &Binding, module_path: &Path) -> Result<Vec<PathBuf>> {
+    let is_occupied = |path: &Path| {
+        (path.exists() || path.is_symlink())
+            && !fs::read_link(path).is_ok_and(|el| el.starts_with(module_path))
+    };
+    let Some(pattern) = &binding.pattern else {
+        return Ok(is_occupied(&binding.external_path)
+            .then(|| binding.external_path.to_path_buf())
+            .into_iter()
+            .collect());
+    };
+    if !binding.external_path.is_dir() {
+        return Ok(Vec::new());
+    }
+    let matcher = GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()?
+        .compile_matcher();
+    let mut paths = Vec::new();
+    for entry in WalkDir::new(&binding.external_path).min_depth(1) {
+        let entry = entry?;
+        let relative_path = entry.path().strip_prefix(&binding.external_path)?;
+        if !entry.file_type().is_dir()
+            && matcher.is_match(relative_path)
+            && is_occupied(entry.path())
+        {
+            paths.push(entry.path().to_path_buf());
+        }
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::symlink;
+
+    use crate::common::test_helper::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn occupied_paths_skip_links_into_the_module() {
+        let temp_dir = TempDir::new("bootstrap");
+        let module_path = temp_dir.path().join("module");
+        let external_path = temp_dir.path().join("config");
+        fs::create_dir_all(module_path.join("linked.conf")).unwrap();
+        fs::create_dir_all(&external_path).unwrap();
+        fs::write(external_path.join("local.conf"), "").unwrap();
+        fs::write(external_path.join("notes.txt"), "").unwrap();
+        symlink(
+            module_path.join("linked.conf"),
+            external_path.join("linked.conf"),
+        )
+        .unwrap();
+
+        let binding = Binding {
+            external_path: external_path.to_path_buf(),
+            pattern: Some("*.conf".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            find_occupied_paths(&binding, &module_path).unwrap(),
+            vec![external_path.join("local.conf")]
+        );
+
+        let binding = Binding {
+            external_path: external_path.join("linked.conf"),
+            ..Default::default()
+        };
+        assert!(find_occupied_paths(&binding, &module_path)
+            .unwrap()
+            .is_empty());
+    }
+}
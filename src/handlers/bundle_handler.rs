@@ -0,0 +1,483 @@
+use std::{
+    fs::{self, File},
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::Result;
+use chrono::Local;
+use commons::utils::file_util::read_file;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use tar::{Archive, Builder, Header};
+use versions::Version;
+
+use crate::{
+    common::{
+        constants,
+        files_helper::copy_preserving,
+        ignore_helper::IgnoreRules,
+        paths_helper::{from_portable_path, to_portable_path},
+        privileges_helper::requires_privileges,
+        state_helper::get_state_dir,
+        versions_helper::get_version_from_name,
+    },
+    types::{
+        bindings::{Binding, VersionBindings},
+        bundles::{BundleManifest, InstalledBundle},
+        errors::ConfigsError,
+    },
+};
+
+use super::{
+    bindings_handler::{read_version_bindings, unbind_all, update_version_bindings},
+    commands_handler::{add_module_with_version, add_version, with_version_checked_out},
+    env_handler::is_valid_key,
+    repository_handler::get_current_repository,
+};
+
+pub fn export_bundle(version: &Version, output: &Path) -> Result<()> {
+    let version_bindings = read_version_bindings(version)?;
+    let manifest = BundleManifest {
+        format_version: constants::BUNDLE_FORMAT_VERSION,
+        module: version.module.module_name.to_string(),
+        config: version.name.to_string(),
+        created_at: Local::now().to_rfc3339(),
+        bindings: version_bindings
+            .entries
+            .iter()
+            .map(|binding| Binding {
+                external_path: to_portable_path(&binding.external_path),
+                owner: None,
+                ..binding.to_owned()
+            })
+            .collect(),
+        env: version_bindings.env,
+    };
+
+    let mut builder = Builder::new(GzEncoder::new(
+        File::create(output)?,
+        Compression::default(),
+    ));
+    builder.follow_symlinks(false);
+
+    let manifest_content = serde_yml::to_string(&manifest)?;
+    let mut header = Header::new_gnu();
+    header.set_size(manifest_content.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(Local::now().timestamp().unsigned_abs());
+    builder.append_data(
+        &mut header,
+        constants::BUNDLE_MANIFEST_FILE,
+        manifest_content.as_bytes(),
+    )?;
+
+    with_version_checked_out(version, |module_path| {
+        let files_dir = Path::new(constants::BUNDLE_FILES_DIR);
+        let mut internal_paths: Vec<PathBuf> = manifest
+            .bindings
+            .iter()
+            .map(|el| el.internal_path.to_path_buf())
+            .collect();
+        internal_paths.push(PathBuf::from(constants::IGNORE_FILE));
+        for internal_path in internal_paths {
+            append_entry(
+                &mut builder,
+                &module_path.join(&internal_path),
+                &files_dir.join(&internal_path),
+            )?;
+        }
+        Ok(())
+    })?;
+
+    let repository = get_current_repository()?;
+    let module_hooks_dir = repository
+        .root_path
+        .join(constants::HOOKS_DIR)
+        .join(&manifest.module);
+    if module_hooks_dir.is_dir() {
+        for entry in fs::read_dir(&module_hooks_dir)? {
+            let path = entry?.path();
+            if path.is_file() {
+                builder.append_path_with_name(
+                    &path,
+                    Path::new(constants::BUNDLE_MODULE_HOOKS_DIR).join(path.file_name().unwrap()),
+                )?;
+            }
+        }
+    }
+    append_entry(
+        &mut builder,
+        &module_hooks_dir.join(&manifest.config),
+        Path::new(constants::BUNDLE_CONFIG_HOOKS_DIR),
+    )?;
+
+    builder.into_inner()?.finish()?;
+    Ok(())
+}
+
+pub fn install_bundle(
+    bundle_path: &Path,
+    module: &Option<String>,
+    config: &Option<String>,
+    allow_validators: bool,
+) -> Result<InstalledBundle> {
+    let staging_dir = get_state_dir()?.join(constants::INSTALL_STAGING_DIR);
+    if staging_dir.exists() {
+        fs::remove_dir_all(&staging_dir)?;
+    }
+    fs::create_dir_all(&staging_dir)?;
+    Archive::new(GzDecoder::new(File::open(bundle_path)?)).unpack(&staging_dir)?;
+
+    let result = install_staged_bundle(&staging_dir, module, config, allow_validators);
+    fs::remove_dir_all(&staging_dir)?;
+    result
+}
+
+fn install_staged_bundle(
+    staging_dir: &Path,
+    module: &Option<String>,
+    config: &Option<String>,
+    allow_validators: bool,
+) -> Result<InstalledBundle> {
+    let manifest: BundleManifest = serde_yml::from_str(&read_file(
+        &staging_dir.join(constants::BUNDLE_MANIFEST_FILE),
+    )?)?;
+    if manifest.format_version > constants::BUNDLE_FORMAT_VERSION {
+        return Err(ConfigsError::UnsupportedBundleVersion(manifest.format_version).into());
+    }
+    if let Some(key) = manifest.env.keys().find(|el| !is_valid_key(el)) {
+        return Err(ConfigsError::InvalidEnvKey(key.to_string()).into());
+    }
+    let mut skipped_validators = Vec::new();
+    let entries = read_manifest_bindings(&manifest, allow_validators, &mut skipped_validators)?;
+    let module_name = module.to_owned().unwrap_or(manifest.module.to_string());
+    let config_name = config.to_owned().unwrap_or(manifest.config.to_string());
+    if let Some(name) = [&module_name, &config_name]
+        .into_iter()
+        .find(|el| !is_plain_name(el))
+    {
+        return Err(ConfigsError::InvalidBundleName(name.to_string()).into());
+    }
+
+    let repository = get_current_repository()?;
+    let new_module = repository.get_module(&module_name).is_err();
+    if new_module {
+        add_module_with_version(&module_name, &config_name)?;
+    } else {
+        let module = repository.get_module(&module_name)?;
+        if module.versions.iter().any(|el| el.name == config_name) {
+            return Err(ConfigsError::ConfigAlreadyExists(module_name, config_name).into());
+        }
+        add_version(&config_name, &module)?;
+    }
+
+    let mut installed_hooks = Vec::new();
+    let result = fill_installed_config(
+        staging_dir,
+        &module_name,
+        &config_name,
+        VersionBindings {
+            entries,
+            env: manifest.env,
+        },
+        &mut installed_hooks,
+    );
+    if result.is_err() {
+        let discarded = installed_hooks
+            .iter()
+            .try_for_each(fs::remove_file)
+            .map_err(anyhow::Error::from)
+            .and_then(|_| discard_installed_config(&module_name, &config_name, new_module));
+        if let Err(error) = discarded {
+            eprintln!("Warning: {error}");
+        }
+    }
+
+    Ok(InstalledBundle {
+        module: module_name,
+        config: config_name,
+        new_module,
+        skipped_hooks: result?,
+        skipped_validators,
+    })
+}
+
+// Bundles come from elsewhere, so nothing in them may reach outside the
+// config or decide about privileges, owners and commands to run.
+fn read_manifest_bindings(
+    manifest: &BundleManifest,
+    allow_validators: bool,
+    skipped_validators: &mut Vec<(PathBuf, String)>,
+) -> Result<Vec<Binding>> {
+    let mut entries = Vec::new();
+    for binding in &manifest.bindings {
+        let is_contained = binding.internal_path.components().next().is_some()
+            && binding
+                .internal_path
+                .components()
+                .all(|el| matches!(el, Component::Normal(_)));
+        if !is_contained {
+            return Err(ConfigsError::UnsafeBundlePath(binding.internal_path.to_path_buf()).into());
+        }
+        let external_path = from_portable_path(&binding.external_path)?;
+        if !external_path.is_absolute() {
+            return Err(ConfigsError::UnsafeBundlePath(binding.external_path.to_path_buf()).into());
+        }
+        let validator = match &binding.validator {
+            Some(validator) if !allow_validators => {
+                skipped_validators.push((external_path.to_path_buf(), validator.to_string()));
+                None
+            }
+            validator => validator.to_owned(),
+        };
+        entries.push(Binding {
+            privileged: requires_privileges(&external_path),
+            owner: None,
+            validator,
+            external_path,
+            ..binding.to_owned()
+        });
+    }
+    Ok(entries)
+}
+
+fn is_plain_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\'])
+}
+
+fn fill_installed_config(
+    staging_dir: &Path,
+    module_name: &str,
+    config_name: &str,
+    version_bindings: VersionBindings,
+    installed_hooks: &mut Vec<PathBuf>,
+) -> Result<Vec<String>> {
+    let repository = get_current_repository()?;
+    let version = get_version_from_name(config_name, &repository.get_module(module_name)?)?;
+    update_version_bindings(&version, |_| version_bindings.to_owned())?;
+
+    let files_dir = staging_dir.join(constants::BUNDLE_FILES_DIR);
+    with_version_checked_out(&version, |module_path| {
+        for entry in fs::read_dir(module_path)? {
+            let path = entry?.path();
+            if path.is_dir() && !path.is_symlink() {
+                fs::remove_dir_all(&path)?;
+            } else {
+                fs::remove_file(&path)?;
+            }
+        }
+        if files_dir.is_dir() {
+            for entry in fs::read_dir(&files_dir)? {
+                let path = entry?.path();
+                copy_preserving(
+                    &path,
+                    &module_path.join(path.file_name().unwrap()),
+                    &IgnoreRules::default(),
+                )?;
+            }
+        }
+        Ok(())
+    })?;
+
+    let hooks_dir = repository
+        .root_path
+        .join(constants::HOOKS_DIR)
+        .join(module_name);
+    let mut skipped_hooks = Vec::new();
+    for (bundle_dir, target_dir) in [
+        (constants::BUNDLE_MODULE_HOOKS_DIR, hooks_dir.to_path_buf()),
+        (
+            constants::BUNDLE_CONFIG_HOOKS_DIR,
+            hooks_dir.join(config_name),
+        ),
+    ] {
+        let bundle_dir = staging_dir.join(bundle_dir);
+        if !bundle_dir.is_dir() {
+            continue;
+        }
+        fs::create_dir_all(&target_dir)?;
+        for entry in fs::read_dir(&bundle_dir)? {
+            let path = entry?.path();
+            let target_path = target_dir.join(path.file_name().unwrap());
+            if target_path.exists() {
+                skipped_hooks.push(target_path.to_string_lossy().to_string());
+                continue;
+            }
+            copy_preserving(&path, &target_path, &IgnoreRules::default())?;
+            installed_hooks.push(target_path);
+        }
+    }
+    Ok(skipped_hooks)
+}
+
+fn discard_installed_config(module_name: &str, config_name: &str, new_module: bool) -> Result<()> {
+    let repository = get_current_repository()?;
+    let mut module = repository.get_module(module_name)?;
+    if new_module {
+        for version in &module.versions {
+            unbind_all(version)?;
+        }
+        repository.remove_module(&module)?;
+        let module_path = repository.root_path.join(&module.directory);
+        if module_path.exists() {
+            fs::remove_dir_all(&module_path)?;
+        }
+    } else {
+        unbind_all(&get_version_from_name(config_name, &module)?)?;
+        module.remove_version(config_name)?;
+    }
+    Ok(())
+}
+
+fn append_entry(builder: &mut Builder<GzEncoder<File>>, path: &Path, name: &Path) -> Result<()> {
+    if path.is_dir() && !path.is_symlink() {
+        builder.append_dir_all(name, path)?;
+    } else if path.exists() || path.is_symlink() {
+        builder.append_path_with_name(path, name)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::{common::test_helper::TempDir, types::bindings::Owner};
+
+    use super::*;
+
+    fn create_manifest(bindings: Vec<Binding>, env: BTreeMap<String, String>) -> BundleManifest {
+        BundleManifest {
+            format_version: constants::BUNDLE_FORMAT_VERSION,
+            module: "shell".to_string(),
+            config: "work".to_string(),
+            created_at: Local::now().to_rfc3339(),
+            bindings,
+            env,
+        }
+    }
+
+    fn write_bundle(path: &Path, manifest: &BundleManifest) {
+        let content = serde_yml::to_string(manifest).unwrap();
+        let mut builder = Builder::new(GzEncoder::new(
+            File::create(path).unwrap(),
+            Compression::default(),
+        ));
+        let mut header = Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        builder
+            .append_data(
+                &mut header,
+                constants::BUNDLE_MANIFEST_FILE,
+                content.as_bytes(),
+            )
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[test]
+    fn rejects_invalid_env_keys_before_creating_the_config() {
+        let dir = TempDir::new("bundle");
+        let bundle_path = dir.path().join("bundle.tar.gz");
+        let env = BTreeMap::from([("X=$(id);Y".to_string(), "value".to_string())]);
+        write_bundle(&bundle_path, &create_manifest(Vec::new(), env));
+
+        // Fails before the repository is opened, so nothing is created.
+        let error = install_bundle(&bundle_path, &None, &None, false).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ConfigsError>(),
+            Some(ConfigsError::InvalidEnvKey(_))
+        ));
+        assert!(!get_state_dir()
+            .unwrap()
+            .join(constants::INSTALL_STAGING_DIR)
+            .exists());
+    }
+
+    #[test]
+    fn rejects_bindings_reaching_outside_the_config() {
+        let dir = TempDir::new("bundle-paths");
+        let bundle_path = dir.path().join("bundle.tar.gz");
+        for internal_path in ["../escape", "/etc/passwd", "files/../../escape", ""] {
+            let binding = Binding {
+                internal_path: PathBuf::from(internal_path),
+                external_path: PathBuf::from("~/.zshrc"),
+                ..Default::default()
+            };
+            write_bundle(
+                &bundle_path,
+                &create_manifest(vec![binding], BTreeMap::new()),
+            );
+
+            let error = install_bundle(&bundle_path, &None, &None, false).unwrap_err();
+            assert!(
+                matches!(
+                    error.downcast_ref::<ConfigsError>(),
+                    Some(ConfigsError::UnsafeBundlePath(path)) if path == Path::new(internal_path)
+                ),
+                "{}",
+                internal_path
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_module_and_config_names_that_are_paths() {
+        let dir = TempDir::new("bundle-names");
+        let bundle_path = dir.path().join("bundle.tar.gz");
+        let mut manifest = create_manifest(Vec::new(), BTreeMap::new());
+        manifest.module = "../shell".to_string();
+        write_bundle(&bundle_path, &manifest);
+
+        let error = install_bundle(&bundle_path, &None, &None, false).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ConfigsError>(),
+            Some(ConfigsError::InvalidBundleName(name)) if name == "../shell"
+        ));
+
+        let error = install_bundle(
+            &bundle_path,
+            &Some("shell".to_string()),
+            &Some("a/b".to_string()),
+            false,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ConfigsError>(),
+            Some(ConfigsError::InvalidBundleName(name)) if name == "a/b"
+        ));
+    }
+
+    #[test]
+    fn manifest_bindings_do_not_carry_privileges_owners_or_validators() {
+        let dir = TempDir::new("bundle-bindings");
+        let external_path = dir.path().join(".zshrc");
+        let binding = Binding {
+            internal_path: PathBuf::from("zshrc"),
+            external_path: external_path.to_path_buf(),
+            privileged: true,
+            owner: Some(Owner { uid: 0, gid: 0 }),
+            validator: Some("zsh -n".to_string()),
+            ..Default::default()
+        };
+        let manifest = create_manifest(vec![binding.to_owned()], BTreeMap::new());
+
+        let mut skipped = Vec::new();
+        let entries = read_manifest_bindings(&manifest, false, &mut skipped).unwrap();
+        assert_eq!(
+            entries,
+            vec![Binding {
+                privileged: false,
+                owner: None,
+                validator: None,
+                ..binding.to_owned()
+            }]
+        );
+        assert_eq!(skipped, vec![(external_path, "zsh -n".to_string())]);
+
+        let mut skipped = Vec::new();
+        let entries = read_manifest_bindings(&manifest, true, &mut skipped).unwrap();
+        assert_eq!(entries[0].validator, Some("zsh -n".to_string()));
+        assert!(skipped.is_empty());
+    }
+}
@@ -1,14 +1,41 @@
-use std::fs::{self, create_dir_all};
+use std::{
+    fs::{self, create_dir_all},
+    path::{Path, PathBuf},
+};
 
 use anyhow::Result;
-use versions::{Module, Version};
+use chrono::Local;
+use versions::{Module, Repository, Version};
 
-use crate::types::bindings::VersionBindings;
+use crate::{
+    common::{
+        constants,
+        paths_helper::get_display_path,
+        signals_helper::SignalGuard,
+        state_helper::get_state_dir,
+        versions_helper::{get_module_path, get_version_from_name},
+    },
+    types::{
+        backups::{RemovalBackup, Snapshot},
+        bindings::{Binding, Deployment, VersionBindings},
+        conflicts::ConflictPolicy,
+        errors::ConfigsError,
+        hooks::{Hook, HookContext},
+    },
+};
 
 use super::{
-    bindings_handler::{read_version_bindings, unbind_all, update_version_bindings},
+    backup_handler::create_snapshot,
+    bindings_handler::{
+        bind_path, is_path_bound, read_version_bindings, unbind_all, unbind_path,
+        update_version_bindings,
+    },
+    bundle_handler::export_bundle,
+    hooks_handler::{run_hooks, run_post_hooks},
     repository_handler::get_current_repository,
-    workspace_handler::unlink_version,
+    workspace_handler::{
+        link_binding, link_version, undeploy_binding, unlink_binding, unlink_version,
+    },
 };
 
 pub fn add_version(name: &str, module: &Module) -> Result<Module> {
@@ -27,7 +54,7 @@ pub fn add_version(name: &str, module: &Module) -> Result<Module> {
     Ok(module)
 }
 
-pub fn remove_version(version: &Version) -> Result<()> {
+pub fn remove_version(version: &Version) -> Result<RemovalBackup> {
     let repository = get_current_repository()?;
     let mut module = repository.get_module(&version.module.module_name)?;
     let module_path = repository.root_path.join(&module.directory);
@@ -35,8 +62,16 @@ pub fn remove_version(version: &Version) -> Result<()> {
     let current_version = module.to_owned().current_version;
     let is_current = current_version.map(|el| el.name).unwrap_or_default() == version.name;
 
+    let mut backup = RemovalBackup {
+        snapshot: None,
+        archives: archive_version(version)?.into_iter().collect(),
+    };
     if is_current {
         unlink_version(version)?;
+        backup.snapshot = create_snapshot(
+            &format!("remove {}/{}", module.name, version.name),
+            &[module_path.to_path_buf()],
+        )?;
         unbind_all(version)?;
         module.remove_version(&version.name)?;
         fs::remove_dir_all(&module_path)?;
@@ -45,7 +80,7 @@ pub fn remove_version(version: &Version) -> Result<()> {
         unbind_all(version)?;
         module.remove_version(&version.name)?;
     }
-    Ok(())
+    Ok(backup)
 }
 
 pub fn add_module(name: &str) -> Result<Module> {
@@ -70,10 +105,15 @@ pub fn add_module_with_version(module_name: &str, version_name: &str) -> Result<
     Ok(())
 }
 
-pub fn remove_module(module: &Module) -> Result<()> {
+pub fn remove_module(module: &Module) -> Result<RemovalBackup> {
     let repository = get_current_repository()?;
     let module_path = repository.root_path.join(&module.directory);
 
+    let mut archives = Vec::new();
+    for version in &module.versions {
+        archives.extend(archive_version(version)?);
+    }
+
     let current_version = module.current_version.to_owned();
 
     if let Some(current_version) = current_version {
@@ -84,8 +124,275 @@ pub fn remove_module(module: &Module) -> Result<()> {
         unbind_all(version)?;
     }
 
+    let snapshot = create_snapshot(
+        &format!("remove {}", module.name),
+        &[module_path.to_path_buf()],
+    )?;
     repository.remove_module(module)?;
     fs::remove_dir_all(&module_path)?;
 
-    Ok(())
+    Ok(RemovalBackup { snapshot, archives })
+}
+
+fn archive_version(version: &Version) -> Result<Option<PathBuf>> {
+    if read_version_bindings(version)?.entries.is_empty() {
+        return Ok(None);
+    }
+    let archives_dir = get_state_dir()?
+        .join(constants::BACKUPS_DIR)
+        .join(constants::BACKUP_ARCHIVES_DIR);
+    fs::create_dir_all(&archives_dir)?;
+    let archive_path = archives_dir.join(format!(
+        "{}-{}-{}.tar.gz",
+        Local::now().format("%Y%m%d-%H%M%S"),
+        version.module.module_name,
+        version.name
+    ));
+    export_bundle(version, &archive_path)?;
+    Ok(Some(archive_path))
+}
+
+pub fn with_version_checked_out<T>(
+    version: &Version,
+    action: impl FnOnce(&Path) -> Result<T>,
+) -> Result<T> {
+    let repository = get_current_repository()?;
+    let mut module = repository.get_module(&version.module.module_name)?;
+    let module_path = repository.root_path.join(&module.directory);
+    let previous = module
+        .current_version
+        .as_ref()
+        .map(|el| el.name.to_string());
+    if previous.as_deref() == Some(version.name.as_str()) {
+        return action(&module_path);
+    }
+
+    // A signal during the swap would leave the checked out version live.
+    let guard = SignalGuard::new()?;
+    module.select_version(&version.name)?;
+    let mut checkout = Checkout {
+        module,
+        module_path,
+        previous,
+        restored: false,
+    };
+    let result = action(&checkout.module_path);
+    checkout.restore()?;
+    if guard.interrupted() {
+        return Err(ConfigsError::Interrupted.into());
+    }
+    result
+}
+
+struct Checkout {
+    module: Module,
+    module_path: PathBuf,
+    previous: Option<String>,
+    restored: bool,
+}
+
+impl Checkout {
+    fn restore(&mut self) -> Result<()> {
+        if self.restored {
+            return Ok(());
+        }
+        self.restored = true;
+        match &self.previous {
+            Some(previous) => {
+                self.module.select_version(previous)?;
+            }
+            None => {
+                self.module.deselect_version()?;
+                fs::remove_dir_all(&self.module_path)?;
+                fs::create_dir_all(&self.module_path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Checkout {
+    fn drop(&mut self) {
+        if let Err(error) = self.restore() {
+            eprintln!("Warning: {error}");
+        }
+    }
+}
+
+pub fn select_config(
+    repository: &Repository,
+    module_name: &str,
+    config: &str,
+    policy: ConflictPolicy,
+) -> Result<Option<Version>> {
+    let mut module = repository.get_module(module_name)?;
+    let new_version = get_version_from_name(config, &module)?;
+
+    let mut diff_bindings = VersionBindings::default();
+    if let Some(current_version) = &module.current_version {
+        diff_bindings = read_version_bindings(current_version)?;
+    }
+    let mut paths: Vec<PathBuf> = diff_bindings
+        .entries
+        .iter()
+        .map(|el| el.external_path.to_path_buf())
+        .collect();
+    for binding in read_version_bindings(&new_version)?.entries {
+        if !paths.contains(&binding.external_path) {
+            paths.push(binding.external_path);
+        }
+    }
+    let hook_context = HookContext {
+        module: module.name.to_string(),
+        old_config: module
+            .current_version
+            .as_ref()
+            .map(|el| el.name.to_string()),
+        new_config: Some(config.to_string()),
+        paths,
+    };
+    run_hooks(&repository.root_path, Hook::PreSelect, &hook_context)?;
+
+    module = repository.select_module(&Some(module))?.unwrap();
+    if let Some(current_version) = &module.current_version {
+        unlink_version(current_version)?;
+    }
+
+    let previous_version = module.current_version.to_owned();
+    let version = module.select_version(config)?;
+
+    if let Err(error) = link_version(&version, Some(diff_bindings.to_owned()), policy) {
+        let module_path = get_module_path(&version);
+        for binding in read_version_bindings(&version)?.entries {
+            let previously_managed = diff_bindings
+                .entries
+                .iter()
+                .any(|el| el.external_path == binding.external_path);
+            if !previously_managed {
+                undeploy_binding(&binding, &module_path)?;
+            }
+        }
+        match &previous_version {
+            Some(previous_version) => {
+                let previous_version = module.select_version(&previous_version.name)?;
+                link_version(&previous_version, None, ConflictPolicy::Overwrite)?;
+            }
+            None => {
+                module.deselect_version()?;
+                fs::remove_dir_all(&module_path)?;
+                fs::create_dir_all(&module_path)?;
+            }
+        }
+        return Err(error);
+    }
+
+    run_post_hooks(&repository.root_path, Hook::PostSelect, &hook_context);
+    Ok(previous_version)
+}
+
+pub fn deselect_module(repository: &Repository, module: &Module) -> Result<Option<Version>> {
+    let Some(current_version) = &module.current_version else {
+        return Ok(None);
+    };
+    let hook_context = HookContext {
+        module: module.name.to_string(),
+        old_config: Some(current_version.name.to_string()),
+        new_config: None,
+        paths: read_version_bindings(current_version)?
+            .entries
+            .into_iter()
+            .map(|el| el.external_path)
+            .collect(),
+    };
+    run_hooks(&repository.root_path, Hook::PreDeselect, &hook_context)?;
+
+    let mut new_module = module.to_owned();
+    new_module.deselect_version()?;
+    let is_current_module = repository
+        .current_module()?
+        .is_some_and(|el| el.name == module.name);
+    if is_current_module {
+        repository.select_module(&None)?;
+    }
+
+    unlink_version(current_version)?;
+
+    let dir_path = repository.root_path.join(&module.directory);
+    create_snapshot(
+        &format!("deselect {}/{}", module.name, current_version.name),
+        &[dir_path.to_path_buf()],
+    )?;
+    fs::remove_dir_all(&dir_path)?;
+    fs::create_dir_all(&dir_path)?;
+
+    run_post_hooks(&repository.root_path, Hook::PostDeselect, &hook_context);
+    Ok(Some(current_version.to_owned()))
+}
+
+pub fn link_path(
+    path: &Path,
+    pattern: &Option<String>,
+    deployment: Deployment,
+    validator: &Option<String>,
+    policy: ConflictPolicy,
+) -> Result<(Version, Binding)> {
+    let repository = get_current_repository()?;
+    let current_version = repository.force_current_module()?.force_current_version()?;
+
+    let already_bound = is_path_bound(&current_version, path)?;
+    if already_bound {
+        return Err(ConfigsError::PathAlreadyBound(path.to_path_buf()).into());
+    };
+
+    let hook_context = get_path_hook_context(&current_version, path);
+    run_hooks(&repository.root_path, Hook::PreLink, &hook_context)?;
+
+    let binding = bind_path(&current_version, path, pattern, deployment, validator)?;
+    if let Err(error) = link_binding(&binding, &get_module_path(&current_version), policy) {
+        unbind_path(&current_version, path)?;
+        return Err(error);
+    }
+
+    run_post_hooks(&repository.root_path, Hook::PostLink, &hook_context);
+    Ok((current_version, binding))
+}
+
+pub fn unlink_path(path: &Path) -> Result<(Version, Binding, Option<Snapshot>)> {
+    let repository = get_current_repository()?;
+    let current_version = repository.force_current_module()?.force_current_version()?;
+
+    let already_bound = is_path_bound(&current_version, path)?;
+    if !already_bound {
+        return Err(ConfigsError::PathNotBound(path.to_path_buf()).into());
+    };
+
+    let hook_context = get_path_hook_context(&current_version, path);
+    run_hooks(&repository.root_path, Hook::PreUnlink, &hook_context)?;
+
+    let module_path = get_module_path(&current_version);
+    let binding = unbind_path(&current_version, path)?;
+    unlink_binding(&binding, &module_path)?;
+
+    let internal_path = module_path.join(&binding.internal_path);
+    let snapshot = create_snapshot(
+        &format!("unlink {}", get_display_path(path)),
+        &[internal_path.to_path_buf()],
+    )?;
+    if internal_path.is_file() {
+        fs::remove_file(&internal_path)?;
+    } else if internal_path.is_dir() {
+        fs::remove_dir_all(&internal_path)?;
+    }
+
+    run_post_hooks(&repository.root_path, Hook::PostUnlink, &hook_context);
+    Ok((current_version, binding, snapshot))
+}
+
+fn get_path_hook_context(version: &Version, path: &Path) -> HookContext {
+    HookContext {
+        module: version.module.module_name.to_string(),
+        old_config: Some(version.name.to_string()),
+        new_config: Some(version.name.to_string()),
+        paths: vec![path.to_path_buf()],
+    }
 }
@@ -0,0 +1,300 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::Result;
+use commons::utils::path_util::get_home_dir_path;
+use globset::GlobBuilder;
+use itertools::Itertools;
+use path_absolutize::Absolutize;
+use versions::Version;
+use walkdir::WalkDir;
+
+use crate::{
+    common::{
+        constants, files_helper::is_copy_of, ignore_helper::IgnoreRules,
+        state_helper::get_state_dir,
+    },
+    types::{
+        bindings::Binding, conflicts::ConflictPolicy, edits::EditReport, errors::ConfigsError,
+    },
+};
+
+use super::{
+    bindings_handler::read_version_bindings,
+    commands_handler::with_version_checked_out,
+    eject_handler::is_ejected,
+    repository_handler::get_current_repository,
+    workspace_handler::{expand_pattern_binding, link_binding},
+};
+
+struct EditTarget {
+    binding: Binding,
+    internal_path: PathBuf,
+    external_path: PathBuf,
+}
+
+pub fn get_editor() -> String {
+    env::var("EDITOR")
+        .or_else(|_| env::var("VISUAL"))
+        .unwrap_or(constants::DEFAULT_EDITOR.to_string())
+}
+
+pub fn edit_config_files(version: &Version, target: &str, editor: &str) -> Result<EditReport> {
+    let bindings = read_version_bindings(version)?.entries;
+    let targets = with_version_checked_out(version, |module_path| {
+        find_edit_targets(&bindings, module_path, target)
+    })?;
+    if targets.is_empty() {
+        return Err(ConfigsError::NoMatchingFiles(target.to_string()).into());
+    }
+
+    let repository = get_current_repository()?;
+    let module = repository.get_module(&version.module.module_name)?;
+    let module_path = repository.root_path.join(&module.directory);
+    let is_active = module
+        .current_version
+        .as_ref()
+        .is_some_and(|el| el.name == version.name);
+
+    let mut report = EditReport {
+        files: targets
+            .iter()
+            .map(|el| el.external_path.to_path_buf())
+            .collect(),
+        ..Default::default()
+    };
+    let staging_dir = get_state_dir()?.join(constants::EDIT_STAGING_DIR);
+    let result = if is_active {
+        edit_in_place(&targets, &module_path, &staging_dir, editor)
+    } else {
+        edit_staged(version, &targets, &staging_dir, editor)
+    };
+    if staging_dir.exists() {
+        fs::remove_dir_all(&staging_dir)?;
+    }
+    result?;
+    if !is_active {
+        return Ok(report);
+    }
+
+    if is_ejected(&repository.root_path, &module.name)? {
+        return Ok(report);
+    }
+    let bindings = targets
+        .iter()
+        .map(|el| &el.binding)
+        .filter(|el| !el.deployment.is_symlink())
+        .unique_by(|el| el.external_path.to_path_buf());
+    for binding in bindings {
+        link_binding(binding, &module_path, ConflictPolicy::Overwrite)?;
+        report.redeployed.push(binding.external_path.to_path_buf());
+    }
+    Ok(report)
+}
+
+fn edit_in_place(
+    targets: &[EditTarget],
+    module_path: &Path,
+    staging_dir: &Path,
+    editor: &str,
+) -> Result<()> {
+    let paths: Vec<PathBuf> = targets
+        .iter()
+        .map(|el| module_path.join(&el.internal_path))
+        .collect();
+    let original_paths = get_staged_paths(targets, staging_dir)?;
+    for (path, original_path) in paths.iter().zip(&original_paths) {
+        fs::create_dir_all(original_path.parent().unwrap())?;
+        fs::copy(path, original_path)?;
+    }
+
+    run_editor(editor, &paths)?;
+
+    if let Err(error) = validate_files(targets, &paths) {
+        for (path, original_path) in paths.iter().zip(&original_paths) {
+            fs::copy(original_path, path)?;
+        }
+        return Err(error);
+    }
+    Ok(())
+}
+
+fn edit_staged(
+    version: &Version,
+    targets: &[EditTarget],
+    staging_dir: &Path,
+    editor: &str,
+) -> Result<()> {
+    let staged_paths = get_staged_paths(targets, staging_dir)?;
+    with_version_checked_out(version, |module_path| {
+        for (target, staged_path) in targets.iter().zip(&staged_paths) {
+            fs::create_dir_all(staged_path.parent().unwrap())?;
+            fs::copy(module_path.join(&target.internal_path), staged_path)?;
+        }
+        Ok(())
+    })?;
+
+    run_editor(editor, &staged_paths)?;
+    validate_files(targets, &staged_paths)?;
+
+    with_version_checked_out(version, |module_path| {
+        for (target, staged_path) in targets.iter().zip(&staged_paths) {
+            let internal_path = module_path.join(&target.internal_path);
+            if !is_copy_of(staged_path, &internal_path, &IgnoreRules::default())? {
+                fs::write(&internal_path, fs::read(staged_path)?)?;
+            }
+        }
+        Ok(())
+    })
+}
+
+fn get_staged_paths(targets: &[EditTarget], staging_dir: &Path) -> Result<Vec<PathBuf>> {
+    if staging_dir.exists() {
+        fs::remove_dir_all(staging_dir)?;
+    }
+    Ok(targets
+        .iter()
+        .enumerate()
+        .map(|(index, el)| {
+            staging_dir
+                .join(index.to_string())
+                .join(el.internal_path.file_name().unwrap())
+        })
+        .collect())
+}
+
+fn find_edit_targets(
+    bindings: &[Binding],
+    module_path: &Path,
+    target: &str,
+) -> Result<Vec<EditTarget>> {
+    let home_path = get_home_dir_path()?;
+    let is_glob = target.contains(['*', '?', '[', '{']);
+    let matcher = if is_glob {
+        Some(
+            GlobBuilder::new(target)
+                .literal_separator(true)
+                .build()?
+                .compile_matcher(),
+        )
+    } else {
+        None
+    };
+    let target_path = Path::new(target).absolutize()?.to_path_buf();
+
+    let mut targets = Vec::new();
+    for binding in bindings {
+        for entry in expand_pattern_binding(binding, module_path)? {
+            let internal_root = module_path.join(&entry.internal_path);
+            for file in WalkDir::new(&internal_root).sort_by_file_name() {
+                let file = file?;
+                if !file.file_type().is_file() {
+                    continue;
+                }
+                let relative_path = file.path().strip_prefix(&internal_root)?;
+                let external_path = if relative_path.as_os_str().is_empty() {
+                    entry.external_path.to_path_buf()
+                } else {
+                    entry.external_path.join(relative_path)
+                };
+                let is_match = match &matcher {
+                    Some(matcher) => {
+                        matcher.is_match(&external_path)
+                            || external_path
+                                .strip_prefix(&home_path)
+                                .is_ok_and(|el| matcher.is_match(el))
+                    }
+                    None => external_path.starts_with(&target_path),
+                };
+                if is_match {
+                    targets.push(EditTarget {
+                        binding: binding.to_owned(),
+                        internal_path: file.path().strip_prefix(module_path)?.to_path_buf(),
+                        external_path,
+                    });
+                }
+            }
+        }
+    }
+    Ok(targets)
+}
+
+fn run_editor(editor: &str, paths: &[PathBuf]) -> Result<()> {
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$@\"", editor))
+        .arg("sh")
+        .args(paths)
+        .status()?;
+    if !status.success() {
+        return Err(ConfigsError::CommandFailed(editor.to_string(), status.code()).into());
+    }
+    Ok(())
+}
+
+fn validate_files(targets: &[EditTarget], paths: &[PathBuf]) -> Result<()> {
+    for (target, path) in targets.iter().zip(paths) {
+        let Some(validator) = &target.binding.validator else {
+            continue;
+        };
+        let status = Command::new("sh")
+            .arg("-c")
+            .arg(format!("{} \"$@\"", validator))
+            .arg("sh")
+            .arg(path)
+            .status()?;
+        if !status.success() {
+            return Err(ConfigsError::ValidationFailed(
+                validator.to_string(),
+                target.external_path.to_path_buf(),
+            )
+            .into());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::test_helper::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn editing_in_place_restores_files_failing_validation() {
+        let temp_dir = TempDir::new("edit");
+        let module_path = temp_dir.path().join("module");
+        let staging_dir = temp_dir.path().join("staging");
+        fs::create_dir_all(&module_path).unwrap();
+        fs::write(module_path.join("config"), "ok").unwrap();
+        let targets = [EditTarget {
+            binding: Binding {
+                validator: Some("grep -q ok".to_string()),
+                ..Default::default()
+            },
+            internal_path: PathBuf::from("config"),
+            external_path: PathBuf::from("/home/user/.config/app/config"),
+        }];
+
+        let editor = "edit() { echo invalid > \"$1\"; }; edit";
+        let error = edit_in_place(&targets, &module_path, &staging_dir, editor).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ConfigsError>(),
+            Some(ConfigsError::ValidationFailed(..))
+        ));
+        assert_eq!(
+            fs::read_to_string(module_path.join("config")).unwrap(),
+            "ok"
+        );
+
+        let editor = "edit() { echo still ok > \"$1\"; }; edit";
+        edit_in_place(&targets, &module_path, &staging_dir, editor).unwrap();
+        assert_eq!(
+            fs::read_to_string(module_path.join("config")).unwrap(),
+            "still ok\n"
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use std::{fs, path::Path};
+
+use anyhow::Result;
+use chrono::Local;
+use commons::utils::file_util::{read_file, write_file};
+use versions::Module;
+
+use crate::{
+    common::{constants, state_helper::get_state_dir, versions_helper::get_module_path},
+    types::ejects::EjectRecord,
+};
+
+use super::{
+    backup_handler::create_snapshot, bindings_handler::read_version_bindings,
+    repository_handler::get_current_repository, workspace_handler::eject_binding,
+};
+
+pub fn eject_modules(modules: Option<Vec<Module>>) -> Result<Vec<EjectRecord>> {
+    let repository = get_current_repository()?;
+    let modules = match modules {
+        Some(modules) => modules,
+        None => repository.list_modules()?,
+    };
+
+    let mut ejected = Vec::new();
+    for module in modules {
+        let Some(version) = &module.current_version else {
+            continue;
+        };
+        let module_path = get_module_path(version);
+        let mut paths = Vec::new();
+        for binding in read_version_bindings(version)?.entries {
+            paths.extend(eject_binding(&binding, &module_path)?);
+        }
+        module.to_owned().deselect_version()?;
+        if repository
+            .current_module()?
+            .is_some_and(|el| el.name == module.name)
+        {
+            repository.select_module(&None)?;
+        }
+        let snapshot = create_snapshot(
+            &format!("eject {}/{}", module.name, version.name),
+            &[module_path.to_path_buf()],
+        )?;
+        fs::remove_dir_all(&module_path)?;
+        fs::create_dir_all(&module_path)?;
+
+        let record = EjectRecord {
+            repository: repository.root_path.to_path_buf(),
+            module: module.name.to_string(),
+            config: version.name.to_string(),
+            ejected_at: Local::now().to_rfc3339(),
+            paths,
+            backup: snapshot.map(|el| el.id),
+        };
+        let mut records = read_records()?;
+        records.retain(|el| !(el.repository == record.repository && el.module == record.module));
+        records.push(record.to_owned());
+        write_records(&records)?;
+        ejected.push(record);
+    }
+    Ok(ejected)
+}
+
+pub fn is_ejected(repository_path: &Path, module_name: &str) -> Result<bool> {
+    Ok(get_eject_record(repository_path, module_name)?.is_some())
+}
+
+pub fn get_eject_record(repository_path: &Path, module_name: &str) -> Result<Option<EjectRecord>> {
+    Ok(read_records()?
+        .into_iter()
+        .find(|el| el.repository == repository_path && el.module == module_name))
+}
+
+pub fn clear_ejected(repository_path: &Path, module_name: &str) -> Result<()> {
+    let mut records = read_records()?;
+    let count = records.len();
+    records.retain(|el| !(el.repository == repository_path && el.module == module_name));
+    if records.len() != count {
+        write_records(&records)?;
+    }
+    Ok(())
+}
+
+fn read_records() -> Result<Vec<EjectRecord>> {
+    let records_path = get_state_dir()?.join(constants::EJECTED_FILE);
+    if !records_path.is_file() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_yml::from_str(&read_file(&records_path)?)?)
+}
+
+fn write_records(records: &[EjectRecord]) -> Result<()> {
+    let state_dir = get_state_dir()?;
+    fs::create_dir_all(&state_dir)?;
+    write_file(
+        &state_dir.join(constants::EJECTED_FILE),
+        &serde_yml::to_string(records)?,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use std::{collections::HashMap, env};
+
+use anyhow::Result;
+use itertools::Itertools;
+use versions::Version;
+
+use crate::{
+    common::constants,
+    types::{errors::ConfigsError, shells::ScriptShell},
+};
+
+use super::{
+    bindings_handler::{read_version_bindings, update_version_bindings},
+    repository_handler::get_current_repository,
+};
+
+pub fn set_env(version: &Version, key: &str, value: &str) -> Result<()> {
+    if !is_valid_key(key) {
+        return Err(ConfigsError::InvalidEnvKey(key.to_string()).into());
+    }
+    update_version_bindings(version, |version_binding| {
+        let mut version_binding = version_binding.to_owned();
+        version_binding
+            .env
+            .insert(key.to_string(), value.to_string());
+        version_binding
+    })
+}
+
+pub fn unset_env(version: &Version, key: &str) -> Result<()> {
+    update_version_bindings(version, |version_binding| {
+        let mut version_binding = version_binding.to_owned();
+        version_binding.env.remove(key);
+        version_binding
+    })
+}
+
+pub fn get_active_env() -> Result<HashMap<String, String>> {
+    let repository = get_current_repository()?;
+    let mut result = HashMap::new();
+    for module in repository.list_modules()? {
+        if let Some(version) = &module.current_version {
+            result.extend(read_version_bindings(version)?.env);
+        }
+    }
+    Ok(result)
+}
+
+pub fn get_template_variables() -> Result<HashMap<String, String>> {
+    let mut variables: HashMap<String, String> = env::vars().collect();
+    variables.extend(get_active_env()?);
+    Ok(variables)
+}
+
+pub fn get_env_script(shell: ScriptShell) -> Result<String> {
+    let mut active_env = get_active_env()?;
+    active_env.retain(|key, _| {
+        let is_valid = is_valid_key(key);
+        if !is_valid {
+            eprintln!("Warning: skipping invalid environment variable name {key:?}");
+        }
+        is_valid
+    });
+    let previous_keys = env::var(constants::ENV_KEYS_VARIABLE).unwrap_or_default();
+    let keys = active_env.keys().sorted().join(":");
+
+    let mut lines = Vec::new();
+    for key in previous_keys
+        .split(':')
+        .filter(|el| !el.is_empty() && is_valid_key(el))
+    {
+        if !active_env.contains_key(key) {
+            lines.push(unset_statement(shell, key));
+        }
+    }
+    for (key, value) in active_env.iter().sorted() {
+        lines.push(export_statement(shell, key, value));
+    }
+    lines.push(export_statement(shell, constants::ENV_KEYS_VARIABLE, &keys));
+    Ok(lines.join("\n"))
+}
+
+pub fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|el| el.is_ascii_alphabetic() || el == '_')
+        && chars.all(|el| el.is_ascii_alphanumeric() || el == '_')
+}
+
+fn export_statement(shell: ScriptShell, key: &str, value: &str) -> String {
+    match shell {
+        ScriptShell::Fish => format!(
+            "set -gx {} '{}'",
+            key,
+            value.replace('\\', "\\\\").replace('\'', "\\'")
+        ),
+        _ => format!("export {}='{}'", key, value.replace('\'', "'\\''")),
+    }
+}
+
+fn unset_statement(shell: ScriptShell, key: &str) -> String {
+    match shell {
+        ScriptShell::Fish => format!("set -e {}", key),
+        _ => format!("unset {}", key),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_variable_names() {
+        for key in ["EDITOR", "_private", "PATH_2", "a"] {
+            assert!(is_valid_key(key), "{key}");
+        }
+    }
+
+    #[test]
+    fn rejects_keys_that_are_not_variable_names() {
+        for key in [
+            "",
+            "2FA",
+            "A-B",
+            "A B",
+            "A;rm -rf ~",
+            "A=B",
+            "$(id)",
+            "ÉDITOR",
+        ] {
+            assert!(!is_valid_key(key), "{key}");
+        }
+    }
+
+    #[test]
+    fn quotes_values() {
+        assert_eq!(
+            export_statement(ScriptShell::Bash, "A", "it's"),
+            "export A='it'\\''s'"
+        );
+        assert_eq!(
+            export_statement(ScriptShell::Fish, "A", "it's"),
+            "set -gx A 'it\\'s'"
+        );
+    }
+}
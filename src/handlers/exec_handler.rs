@@ -0,0 +1,159 @@
+use std::process::{Command, ExitStatus};
+
+use anyhow::Result;
+use versions::Repository;
+
+use crate::{
+    common::signals_helper::SignalGuard,
+    types::{conflicts::ConflictPolicy, errors::ConfigsError},
+};
+
+use super::{
+    commands_handler::{deselect_module, select_config},
+    eject_handler::is_ejected,
+    env_handler::get_active_env,
+    repository_handler::get_current_repository,
+};
+
+pub fn exec_with_config(
+    module_name: &str,
+    config: &str,
+    policy: ConflictPolicy,
+    command: &[String],
+) -> Result<ExitStatus> {
+    let Some((program, args)) = command.split_first() else {
+        return Err(ConfigsError::MissingCommand.into());
+    };
+
+    let repository = get_current_repository()?;
+    let module = repository.get_module(module_name)?;
+    if is_ejected(&repository.root_path, &module.name)? {
+        return Err(ConfigsError::ModuleEjected(module.name).into());
+    }
+    let previous_module = repository.current_module()?.map(|el| el.name);
+    let previous_config = module.current_version.map(|el| el.name);
+
+    // The child shares our process group, so an interrupt or hangup reaches it
+    // anyway; ignoring these and termination requests here leaves us alive to
+    // restore the previous selection once the child exits.
+    let _guard = SignalGuard::new()?;
+
+    let already_selected = previous_config.as_deref() == Some(config)
+        && previous_module.as_deref() == Some(module_name);
+
+    run_with_selection(
+        || {
+            if !already_selected {
+                select_config(&repository, module_name, config, policy)?;
+            }
+            Ok(())
+        },
+        || {
+            Ok(Command::new(program)
+                .args(args)
+                .envs(get_active_env()?)
+                .status()?)
+        },
+        || {
+            if !already_selected {
+                restore_selection(&repository, module_name, &previous_config, &previous_module)?;
+            }
+            Ok(())
+        },
+    )
+}
+
+fn run_with_selection(
+    select: impl FnOnce() -> Result<()>,
+    run: impl FnOnce() -> Result<ExitStatus>,
+    restore: impl FnOnce() -> Result<()>,
+) -> Result<ExitStatus> {
+    select()?;
+    let status = run();
+    restore()?;
+    status
+}
+
+fn restore_selection(
+    repository: &Repository,
+    module_name: &str,
+    previous_config: &Option<String>,
+    previous_module: &Option<String>,
+) -> Result<()> {
+    match previous_config {
+        Some(previous_config) => {
+            select_config(
+                repository,
+                module_name,
+                previous_config,
+                ConflictPolicy::Backup,
+            )?;
+        }
+        None => {
+            deselect_module(repository, &repository.get_module(module_name)?)?;
+        }
+    }
+
+    let previous_module = match previous_module {
+        Some(name) => Some(repository.get_module(name)?),
+        None => None,
+    };
+    repository.select_module(&previous_module)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    fn run_logged(
+        select: Result<()>,
+        command: &[&str],
+    ) -> (Result<Option<i32>>, Vec<&'static str>) {
+        let log = RefCell::new(Vec::new());
+        let result = run_with_selection(
+            || {
+                log.borrow_mut().push("select");
+                select
+            },
+            || {
+                log.borrow_mut().push("run");
+                Ok(Command::new(command[0]).args(&command[1..]).status()?)
+            },
+            || {
+                log.borrow_mut().push("restore");
+                Ok(())
+            },
+        );
+        (result.map(|el| el.code()), log.into_inner())
+    }
+
+    #[test]
+    fn previous_selection_is_restored_after_the_command_exits() {
+        let (result, log) = run_logged(Ok(()), &["sh", "-c", "exit 3"]);
+
+        assert_eq!(result.unwrap(), Some(3));
+        assert_eq!(log, vec!["select", "run", "restore"]);
+    }
+
+    #[test]
+    fn previous_selection_is_restored_when_the_command_cannot_run() {
+        let (result, log) = run_logged(Ok(()), &["configs-missing-command"]);
+
+        assert!(result.is_err());
+        assert_eq!(log, vec!["select", "run", "restore"]);
+    }
+
+    #[test]
+    fn nothing_runs_when_selecting_fails() {
+        let (result, log) = run_logged(
+            Err(ConfigsError::ConfigNotFound.into()),
+            &["sh", "-c", "exit 0"],
+        );
+
+        assert!(result.is_err());
+        assert_eq!(log, vec!["select"]);
+    }
+}
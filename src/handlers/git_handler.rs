@@ -0,0 +1,233 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::Result;
+
+use crate::{
+    common::{constants, ignore_helper::IgnoreRules},
+    types::errors::ConfigsError,
+};
+
+use super::settings_handler::read_settings;
+
+pub fn auto_commit(repository_path: &Path, paths: &[PathBuf], message: &str) -> Result<bool> {
+    if !read_settings(repository_path)?.git.auto_commit {
+        return Ok(false);
+    }
+    commit_paths(repository_path, paths, message)
+}
+
+pub fn commit_paths(repository_path: &Path, paths: &[PathBuf], message: &str) -> Result<bool> {
+    if !is_git_repository(repository_path) {
+        return Err(ConfigsError::NotAGitRepository(repository_path.to_path_buf()).into());
+    }
+
+    let mut pathspecs: Vec<String> = paths
+        .iter()
+        .filter_map(|path| path.strip_prefix(repository_path).ok())
+        .map(|path| {
+            if path.as_os_str().is_empty() {
+                ".".to_string()
+            } else {
+                path.to_string_lossy().to_string()
+            }
+        })
+        .collect();
+    pathspecs.extend(
+        [constants::BINDINGS_CONFIG_FILE, constants::SETTINGS_FILE].map(|el| el.to_string()),
+    );
+    // Paths removed by the operation can only be staged when git knows them.
+    pathspecs.retain(|el| repository_path.join(el).exists() || is_tracked(repository_path, el));
+    if pathspecs.is_empty() {
+        return Ok(false);
+    }
+    // Entries ignored by their module stay out of git, as they do out of
+    // the module when adopting.
+    let ignored_files = find_ignored_files(repository_path, &pathspecs)?;
+    pathspecs.extend(
+        ignored_files
+            .iter()
+            .map(|el| format!(":(exclude,literal){}", el)),
+    );
+
+    let mut add_args = vec!["add", "-A", "--"];
+    add_args.extend(pathspecs.iter().map(|el| el.as_str()));
+    run_git(repository_path, &add_args)?;
+
+    if !has_staged_changes(repository_path, &pathspecs)? {
+        return Ok(false);
+    }
+    // Limited to the pathspecs, so changes the user staged stay out.
+    let mut commit_args = vec!["commit", "--quiet", "-m", message, "--"];
+    commit_args.extend(pathspecs.iter().map(|el| el.as_str()));
+    run_git(repository_path, &commit_args)?;
+    Ok(true)
+}
+
+fn find_ignored_files(repository_path: &Path, pathspecs: &[String]) -> Result<Vec<String>> {
+    let mut args = vec![
+        "ls-files",
+        "-z",
+        "--cached",
+        "--others",
+        "--exclude-standard",
+        "--",
+    ];
+    args.extend(pathspecs.iter().map(|el| el.as_str()));
+    let output = run_git_bytes(repository_path, &args)?;
+
+    let mut ignored_files = Vec::new();
+    for file in output.split(|el| *el == 0).filter(|el| !el.is_empty()) {
+        let file = String::from_utf8_lossy(file).to_string();
+        if is_ignored_by_module(repository_path, &repository_path.join(&file))? {
+            ignored_files.push(file);
+        }
+    }
+    Ok(ignored_files)
+}
+
+/// Whether the closest directory above `path` with an ignore file, a module
+/// directory, ignores it.
+fn is_ignored_by_module(repository_path: &Path, path: &Path) -> Result<bool> {
+    for ancestor in path.ancestors().skip(1) {
+        if !ancestor.starts_with(repository_path) || ancestor == repository_path {
+            break;
+        }
+        if ancestor.join(constants::IGNORE_FILE).is_file() {
+            let ignore_rules = IgnoreRules::load(ancestor)?;
+            return Ok(ignore_rules.is_ignored(path.strip_prefix(ancestor)?, false));
+        }
+    }
+    Ok(false)
+}
+
+fn is_tracked(repository_path: &Path, pathspec: &str) -> bool {
+    run_git(
+        repository_path,
+        &["ls-files", "--error-unmatch", "--", pathspec],
+    )
+    .is_ok()
+}
+
+pub fn is_git_repository(repository_path: &Path) -> bool {
+    run_git(repository_path, &["rev-parse", "--is-inside-work-tree"]).is_ok()
+}
+
+pub fn has_staged_changes(repository_path: &Path, pathspecs: &[String]) -> Result<bool> {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(repository_path)
+        .args(["diff", "--cached", "--quiet", "--"])
+        .args(pathspecs)
+        .status()?;
+    Ok(!status.success())
+}
+
+pub fn run_git(repository_path: &Path, args: &[&str]) -> Result<String> {
+    let output = run_git_bytes(repository_path, args)?;
+    Ok(String::from_utf8_lossy(&output).to_string())
+}
+
+pub fn run_git_bytes(repository_path: &Path, args: &[&str]) -> Result<Vec<u8>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repository_path)
+        .args(args)
+        .output()?;
+    if !output.status.success() {
+        return Err(ConfigsError::GitFailed(
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        )
+        .into());
+    }
+    Ok(output.stdout)
+}
+
+pub fn get_head(repository_path: &Path) -> Result<String> {
+    Ok(run_git(repository_path, &["rev-parse", "HEAD"])?
+        .trim()
+        .to_string())
+}
+
+pub fn get_current_branch(repository_path: &Path) -> Result<String> {
+    Ok(
+        run_git(repository_path, &["rev-parse", "--abbrev-ref", "HEAD"])?
+            .trim()
+            .to_string(),
+    )
+}
+
+pub fn get_changed_files(repository_path: &Path, from: &str, to: &str) -> Result<Vec<PathBuf>> {
+    Ok(
+        run_git(repository_path, &["diff", "--name-only", from, to])?
+            .lines()
+            .map(PathBuf::from)
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::common::test_helper::TempDir;
+
+    use super::*;
+
+    fn init_repository(path: &Path) {
+        run_git(path, &["init", "--quiet"]).unwrap();
+        run_git(path, &["config", "user.name", "tester"]).unwrap();
+        run_git(path, &["config", "user.email", "tester@localhost"]).unwrap();
+    }
+
+    #[test]
+    fn committing_leaves_other_staged_changes_alone() {
+        let temp_dir = TempDir::new("git-commit");
+        let root = temp_dir.path();
+        init_repository(root);
+        fs::create_dir_all(root.join("module")).unwrap();
+        fs::write(root.join("module/file"), "content").unwrap();
+        fs::write(root.join("unrelated"), "staged").unwrap();
+        run_git(root, &["add", "unrelated"]).unwrap();
+
+        assert!(commit_paths(root, &[root.join("module")], "commit module").unwrap());
+
+        let committed = run_git(root, &["show", "--name-only", "--format=", "HEAD"]).unwrap();
+        assert_eq!(committed.trim(), "module/file");
+        let staged = run_git(root, &["diff", "--cached", "--name-only"]).unwrap();
+        assert_eq!(staged.trim(), "unrelated");
+    }
+
+    #[test]
+    fn committing_skips_removed_untracked_paths() {
+        let temp_dir = TempDir::new("git-removed");
+        let root = temp_dir.path();
+        init_repository(root);
+        fs::write(root.join("kept"), "content").unwrap();
+
+        assert!(commit_paths(root, &[root.join("kept"), root.join("removed")], "commit").unwrap());
+        assert!(!commit_paths(root, &[root.join("removed")], "commit").unwrap());
+    }
+
+    #[test]
+    fn committing_leaves_out_files_ignored_by_the_module() {
+        let temp_dir = TempDir::new("git-ignored");
+        let root = temp_dir.path();
+        init_repository(root);
+        fs::create_dir_all(root.join("module/nvim/cache")).unwrap();
+        fs::write(root.join("module").join(constants::IGNORE_FILE), "cache/\n").unwrap();
+        fs::write(root.join("module/nvim/init.lua"), "content").unwrap();
+        fs::write(root.join("module/nvim/cache/state"), "runtime").unwrap();
+
+        assert!(commit_paths(root, &[root.join("module")], "commit module").unwrap());
+
+        let committed = run_git(root, &["ls-files"]).unwrap();
+        assert_eq!(
+            committed.lines().collect::<Vec<_>>(),
+            vec!["module/.configsignore", "module/nvim/init.lua"]
+        );
+    }
+}
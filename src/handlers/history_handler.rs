@@ -0,0 +1,485 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::Result;
+use chrono::Local;
+use commons::utils::file_util::{read_file, write_file};
+
+use crate::{
+    common::{constants, state_helper::get_state_dir, versions_helper::get_version_from_name},
+    types::{
+        bindings::Binding,
+        conflicts::ConflictPolicy,
+        errors::ConfigsError,
+        history::{HistoryRecord, Operation, Selection},
+    },
+};
+
+use super::{
+    backup_handler::restore_snapshot,
+    bindings_handler::add_binding,
+    commands_handler::{
+        deselect_module, remove_module, remove_version, select_config, unlink_path,
+    },
+    eject_handler::clear_ejected,
+    env_handler::{set_env, unset_env},
+    repository_handler::get_current_repository,
+    workspace_handler::link_binding,
+};
+
+pub fn record_operation(
+    operation: Operation,
+    before: Option<Selection>,
+    after: Option<Selection>,
+    bindings: Vec<Binding>,
+    backup: Option<String>,
+) -> Result<HistoryRecord> {
+    let repository = get_current_repository()?;
+    let mut history = read_history()?;
+    let record = HistoryRecord {
+        id: history.iter().map(|el| el.id).max().unwrap_or_default() + 1,
+        timestamp: Local::now().to_rfc3339(),
+        repository: repository.root_path.to_path_buf(),
+        operation,
+        before,
+        after,
+        bindings,
+        backup,
+        undone: false,
+    };
+    history.push(record.to_owned());
+    write_history(&history)?;
+    Ok(record)
+}
+
+pub fn get_repository_history() -> Result<Vec<HistoryRecord>> {
+    let repository = get_current_repository()?;
+    Ok(read_history()?
+        .into_iter()
+        .filter(|el| el.repository == repository.root_path)
+        .collect())
+}
+
+pub fn undo_operations(count: usize) -> Result<Vec<HistoryRecord>> {
+    let candidates: Vec<HistoryRecord> = get_repository_history()?
+        .into_iter()
+        .filter(|el| !el.undone)
+        .rev()
+        .take(count)
+        .collect();
+
+    let mut undone = Vec::new();
+    for record in candidates {
+        undo_operation(&record)?;
+
+        let mut history = read_history()?;
+        if let Some(entry) = history.iter_mut().find(|el| el.id == record.id) {
+            entry.undone = true;
+        }
+        write_history(&history)?;
+        undone.push(record);
+    }
+    Ok(undone)
+}
+
+#[derive(Debug, PartialEq)]
+enum UndoStep {
+    RemoveModule(String),
+    RemoveConfig(String, String),
+    SelectConfig(String, String),
+    DeselectModule(String),
+    SelectModule(Option<String>),
+    EnsureSelected(Selection),
+    UnlinkPath(PathBuf),
+    RestoreSnapshot(String),
+    Relink(Vec<Binding>),
+    ClearEjected(String),
+    SetEnv(Selection, String, String),
+    UnsetEnv(Selection, String),
+}
+
+fn plan_undo(record: &HistoryRecord) -> Result<Vec<UndoStep>> {
+    let steps = match &record.operation {
+        Operation::Add {
+            module,
+            new_module: true,
+            ..
+        } => vec![UndoStep::RemoveModule(module.to_string())],
+        Operation::Add { module, config, .. } => vec![UndoStep::RemoveConfig(
+            module.to_string(),
+            config.to_string(),
+        )],
+        Operation::Remove { .. } => {
+            return Err(ConfigsError::NotUndoable(
+                record.operation.to_string(),
+                record.backup.to_owned(),
+            )
+            .into())
+        }
+        Operation::Select {
+            module,
+            previous_module,
+            ..
+        } => vec![
+            match &record.before {
+                Some(before) => {
+                    UndoStep::SelectConfig(module.to_string(), before.config.to_string())
+                }
+                None => UndoStep::DeselectModule(module.to_string()),
+            },
+            UndoStep::SelectModule(previous_module.to_owned()),
+        ],
+        Operation::Deselect { module, config } => vec![UndoStep::SelectConfig(
+            module.to_string(),
+            config.to_string(),
+        )],
+        Operation::Link { path, .. } => record
+            .after
+            .iter()
+            .map(|el| UndoStep::EnsureSelected(el.to_owned()))
+            .chain([UndoStep::UnlinkPath(path.to_path_buf())])
+            .collect(),
+        Operation::Unlink { .. } => record
+            .before
+            .iter()
+            .map(|el| UndoStep::EnsureSelected(el.to_owned()))
+            .chain(
+                record
+                    .backup
+                    .iter()
+                    .map(|el| UndoStep::RestoreSnapshot(el.to_string())),
+            )
+            .chain([UndoStep::Relink(record.bindings.to_owned())])
+            .collect(),
+        Operation::Eject { module, config } => vec![
+            UndoStep::SelectConfig(module.to_string(), config.to_string()),
+            UndoStep::ClearEjected(module.to_string()),
+        ],
+        Operation::SetEnv {
+            module,
+            config,
+            key,
+            previous,
+        }
+        | Operation::UnsetEnv {
+            module,
+            config,
+            key,
+            previous,
+        } => {
+            let selection = Selection {
+                module: module.to_string(),
+                config: config.to_string(),
+            };
+            match previous {
+                Some(value) => vec![UndoStep::SetEnv(
+                    selection,
+                    key.to_string(),
+                    value.to_string(),
+                )],
+                None => vec![UndoStep::UnsetEnv(selection, key.to_string())],
+            }
+        }
+    };
+    Ok(steps)
+}
+
+fn undo_operation(record: &HistoryRecord) -> Result<()> {
+    let steps = plan_undo(record)?;
+    let repository = get_current_repository()?;
+    for step in steps {
+        match step {
+            UndoStep::RemoveModule(module) => {
+                remove_module(&repository.get_module(&module)?)?;
+            }
+            UndoStep::RemoveConfig(module, config) => {
+                let module = repository.get_module(&module)?;
+                remove_version(&get_version_from_name(&config, &module)?)?;
+            }
+            UndoStep::SelectConfig(module, config) => {
+                select_config(&repository, &module, &config, ConflictPolicy::Backup)?;
+            }
+            UndoStep::DeselectModule(module) => {
+                deselect_module(&repository, &repository.get_module(&module)?)?;
+            }
+            UndoStep::SelectModule(module) => {
+                let module = match module {
+                    Some(name) => Some(repository.get_module(&name)?),
+                    None => None,
+                };
+                repository.select_module(&module)?;
+            }
+            UndoStep::EnsureSelected(selection) => ensure_selected(&selection)?,
+            UndoStep::UnlinkPath(path) => {
+                unlink_path(&path)?;
+            }
+            UndoStep::RestoreSnapshot(backup) => {
+                restore_snapshot(&backup)?;
+            }
+            UndoStep::Relink(bindings) => {
+                let version = repository.force_current_module()?.force_current_version()?;
+                let module_path = version
+                    .module
+                    .repository_path
+                    .join(&version.module.module_dir);
+                for binding in &bindings {
+                    add_binding(&version, binding)?;
+                    link_binding(binding, &module_path, ConflictPolicy::Backup)?;
+                }
+            }
+            UndoStep::ClearEjected(module) => clear_ejected(&repository.root_path, &module)?,
+            UndoStep::SetEnv(selection, key, value) => {
+                let module = repository.get_module(&selection.module)?;
+                set_env(
+                    &get_version_from_name(&selection.config, &module)?,
+                    &key,
+                    &value,
+                )?;
+            }
+            UndoStep::UnsetEnv(selection, key) => {
+                let module = repository.get_module(&selection.module)?;
+                unset_env(&get_version_from_name(&selection.config, &module)?, &key)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn ensure_selected(selection: &Selection) -> Result<()> {
+    let repository = get_current_repository()?;
+    let current = repository
+        .current_module()?
+        .and_then(|module| module.current_version)
+        .map(|version| Selection {
+            module: version.module.module_name,
+            config: version.name,
+        });
+    if current.as_ref() != Some(selection) {
+        return Err(ConfigsError::SelectionChanged(
+            selection.module.to_string(),
+            selection.config.to_string(),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn read_history() -> Result<Vec<HistoryRecord>> {
+    let history_path = get_state_dir()?.join(constants::HISTORY_FILE);
+    if !history_path.is_file() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_yml::from_str(&read_file(&history_path)?)?)
+}
+
+fn write_history(history: &[HistoryRecord]) -> Result<()> {
+    let state_dir = get_state_dir()?;
+    fs::create_dir_all(&state_dir)?;
+    write_file(
+        &state_dir.join(constants::HISTORY_FILE),
+        &serde_yml::to_string(history)?,
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_record(
+        operation: Operation,
+        before: Option<Selection>,
+        after: Option<Selection>,
+    ) -> HistoryRecord {
+        HistoryRecord {
+            id: 1,
+            timestamp: String::new(),
+            repository: PathBuf::from("/repository"),
+            operation,
+            before,
+            after,
+            bindings: Vec::new(),
+            backup: None,
+            undone: false,
+        }
+    }
+
+    fn selection(module: &str, config: &str) -> Selection {
+        Selection {
+            module: module.to_string(),
+            config: config.to_string(),
+        }
+    }
+
+    #[test]
+    fn undoing_link_unlinks_the_path_in_the_same_selection() {
+        let record = create_record(
+            Operation::Link {
+                module: "vim".to_string(),
+                config: "default".to_string(),
+                path: PathBuf::from("/home/user/.vimrc"),
+            },
+            Some(selection("vim", "default")),
+            Some(selection("vim", "default")),
+        );
+
+        assert_eq!(
+            plan_undo(&record).unwrap(),
+            vec![
+                UndoStep::EnsureSelected(selection("vim", "default")),
+                UndoStep::UnlinkPath(PathBuf::from("/home/user/.vimrc")),
+            ]
+        );
+    }
+
+    #[test]
+    fn undoing_unlink_restores_the_snapshot_and_relinks_bindings() {
+        let binding = Binding {
+            internal_path: PathBuf::from(".vimrc"),
+            external_path: PathBuf::from("/home/user/.vimrc"),
+            ..Default::default()
+        };
+        let mut record = create_record(
+            Operation::Unlink {
+                module: "vim".to_string(),
+                config: "default".to_string(),
+                path: PathBuf::from("/home/user/.vimrc"),
+            },
+            Some(selection("vim", "default")),
+            Some(selection("vim", "default")),
+        );
+        record.bindings = vec![binding.to_owned()];
+        record.backup = Some("unlink-vimrc".to_string());
+
+        assert_eq!(
+            plan_undo(&record).unwrap(),
+            vec![
+                UndoStep::EnsureSelected(selection("vim", "default")),
+                UndoStep::RestoreSnapshot("unlink-vimrc".to_string()),
+                UndoStep::Relink(vec![binding]),
+            ]
+        );
+    }
+
+    #[test]
+    fn undoing_select_restores_the_previous_config_and_module() {
+        let record = create_record(
+            Operation::Select {
+                module: "vim".to_string(),
+                config: "work".to_string(),
+                previous_module: Some("zsh".to_string()),
+            },
+            Some(selection("vim", "default")),
+            Some(selection("vim", "work")),
+        );
+
+        assert_eq!(
+            plan_undo(&record).unwrap(),
+            vec![
+                UndoStep::SelectConfig("vim".to_string(), "default".to_string()),
+                UndoStep::SelectModule(Some("zsh".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn undoing_first_select_deselects_the_module() {
+        let record = create_record(
+            Operation::Select {
+                module: "vim".to_string(),
+                config: "work".to_string(),
+                previous_module: None,
+            },
+            None,
+            Some(selection("vim", "work")),
+        );
+
+        assert_eq!(
+            plan_undo(&record).unwrap(),
+            vec![
+                UndoStep::DeselectModule("vim".to_string()),
+                UndoStep::SelectModule(None),
+            ]
+        );
+    }
+
+    #[test]
+    fn undoing_eject_selects_the_config_again() {
+        let record = create_record(
+            Operation::Eject {
+                module: "vim".to_string(),
+                config: "default".to_string(),
+            },
+            Some(selection("vim", "default")),
+            None,
+        );
+
+        assert_eq!(
+            plan_undo(&record).unwrap(),
+            vec![
+                UndoStep::SelectConfig("vim".to_string(), "default".to_string()),
+                UndoStep::ClearEjected("vim".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn undoing_env_changes_restores_the_previous_value() {
+        let set_record = create_record(
+            Operation::SetEnv {
+                module: "shell".to_string(),
+                config: "default".to_string(),
+                key: "EDITOR".to_string(),
+                previous: None,
+            },
+            None,
+            None,
+        );
+        let unset_record = create_record(
+            Operation::UnsetEnv {
+                module: "shell".to_string(),
+                config: "default".to_string(),
+                key: "EDITOR".to_string(),
+                previous: Some("vim".to_string()),
+            },
+            None,
+            None,
+        );
+
+        assert_eq!(
+            plan_undo(&set_record).unwrap(),
+            vec![UndoStep::UnsetEnv(
+                selection("shell", "default"),
+                "EDITOR".to_string()
+            )]
+        );
+        assert_eq!(
+            plan_undo(&unset_record).unwrap(),
+            vec![UndoStep::SetEnv(
+                selection("shell", "default"),
+                "EDITOR".to_string(),
+                "vim".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn removing_is_not_undoable() {
+        let mut record = create_record(
+            Operation::Remove {
+                module: "vim".to_string(),
+                config: Some("work".to_string()),
+            },
+            None,
+            None,
+        );
+        record.backup = Some("remove-vim".to_string());
+
+        let error = plan_undo(&record).unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<ConfigsError>(),
+            Some(ConfigsError::NotUndoable(operation, Some(backup)))
+                if operation == "remove config work from vim" && backup == "remove-vim"
+        ));
+    }
+}
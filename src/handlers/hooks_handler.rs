@@ -0,0 +1,159 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::Result;
+use itertools::Itertools;
+
+use crate::{
+    common::constants,
+    types::{
+        errors::ConfigsError,
+        hooks::{Hook, HookContext},
+    },
+};
+
+pub fn run_post_hooks(repository_path: &Path, hook: Hook, context: &HookContext) {
+    if let Err(error) = run_hooks(repository_path, hook, context) {
+        eprintln!("Warning: {error}");
+    }
+}
+
+pub fn run_hooks(repository_path: &Path, hook: Hook, context: &HookContext) -> Result<()> {
+    for script in find_hook_scripts(repository_path, hook, context) {
+        let status = Command::new(&script)
+            .current_dir(repository_path)
+            .env("CONFIGS_HOOK", hook.to_string())
+            .env("CONFIGS_REPOSITORY", repository_path)
+            .env("CONFIGS_MODULE", &context.module)
+            .env(
+                "CONFIGS_OLD_CONFIG",
+                context.old_config.to_owned().unwrap_or_default(),
+            )
+            .env(
+                "CONFIGS_NEW_CONFIG",
+                context.new_config.to_owned().unwrap_or_default(),
+            )
+            .env(
+                "CONFIGS_PATHS",
+                context
+                    .paths
+                    .iter()
+                    .map(|el| el.to_string_lossy())
+                    .join("\n"),
+            )
+            .status()?;
+        if !status.success() {
+            return Err(ConfigsError::HookFailed(hook.to_string(), script).into());
+        }
+    }
+    Ok(())
+}
+
+fn find_hook_scripts(repository_path: &Path, hook: Hook, context: &HookContext) -> Vec<PathBuf> {
+    let module_hooks_dir = repository_path
+        .join(constants::HOOKS_DIR)
+        .join(&context.module);
+    let config = match hook {
+        Hook::PreDeselect | Hook::PostDeselect | Hook::PreUnlink | Hook::PostUnlink => {
+            &context.old_config
+        }
+        _ => &context.new_config,
+    };
+
+    let mut scripts = vec![module_hooks_dir.join(hook.to_string())];
+    if let Some(config) = config {
+        scripts.push(module_hooks_dir.join(config).join(hook.to_string()));
+    }
+    scripts.retain(|script| script.is_file());
+    scripts
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, os::unix::fs::PermissionsExt};
+
+    use crate::common::test_helper::TempDir;
+
+    use super::*;
+
+    fn create_hook(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, format!("#!/bin/sh\n{}\n", content)).unwrap();
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    fn create_context() -> HookContext {
+        HookContext {
+            module: "vim".to_string(),
+            old_config: Some("default".to_string()),
+            new_config: Some("work".to_string()),
+            paths: vec![PathBuf::from("/home/user/.vimrc")],
+        }
+    }
+
+    #[test]
+    fn module_hooks_run_before_config_hooks() {
+        let temp_dir = TempDir::new("hooks-order");
+        let root = temp_dir.path();
+        let hooks_dir = root.join(constants::HOOKS_DIR).join("vim");
+        for path in [
+            hooks_dir.join("post-select"),
+            hooks_dir.join("work/post-select"),
+            hooks_dir.join("default/post-deselect"),
+        ] {
+            create_hook(&path, "true");
+        }
+
+        assert_eq!(
+            find_hook_scripts(root, Hook::PostSelect, &create_context()),
+            vec![
+                hooks_dir.join("post-select"),
+                hooks_dir.join("work/post-select")
+            ]
+        );
+        assert_eq!(
+            find_hook_scripts(root, Hook::PostDeselect, &create_context()),
+            vec![hooks_dir.join("default/post-deselect")]
+        );
+        assert!(find_hook_scripts(root, Hook::PreLink, &create_context()).is_empty());
+    }
+
+    #[test]
+    fn hooks_receive_the_operation_in_the_environment() {
+        let temp_dir = TempDir::new("hooks-env");
+        let root = temp_dir.path();
+        create_hook(
+            &root.join(constants::HOOKS_DIR).join("vim/post-select"),
+            "printf '%s|%s|%s|%s|%s|%s' \"$CONFIGS_HOOK\" \"$CONFIGS_REPOSITORY\" \
+             \"$CONFIGS_MODULE\" \"$CONFIGS_OLD_CONFIG\" \"$CONFIGS_NEW_CONFIG\" \
+             \"$CONFIGS_PATHS\" > env.txt",
+        );
+
+        run_hooks(root, Hook::PostSelect, &create_context()).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(root.join("env.txt")).unwrap(),
+            format!(
+                "post-select|{}|vim|default|work|/home/user/.vimrc",
+                root.display()
+            )
+        );
+    }
+
+    #[test]
+    fn failing_hooks_are_reported() {
+        let temp_dir = TempDir::new("hooks-failure");
+        let root = temp_dir.path();
+        let script = root.join(constants::HOOKS_DIR).join("vim/pre-link");
+        create_hook(&script, "exit 1");
+
+        let error = run_hooks(root, Hook::PreLink, &create_context()).unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<ConfigsError>(),
+            Some(ConfigsError::HookFailed(hook, path)) if hook == "pre-link" && path == &script
+        ));
+    }
+}
@@ -0,0 +1,693 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::Result;
+use commons::utils::path_util::get_home_dir_path;
+use itertools::Itertools;
+use symlink::symlink_auto;
+use versions::Version;
+use walkdir::WalkDir;
+
+use crate::{
+    common::{
+        chezmoi_helper::{convert_template, parse_name},
+        files_helper::{copy_preserving, get_mode, set_mode},
+        ignore_helper::IgnoreRules,
+        paths_helper::get_internal_path,
+        versions_helper::get_module_path,
+    },
+    types::{
+        bindings::{Binding, Deployment},
+        conflicts::ConflictPolicy,
+        errors::ConfigsError,
+        imports::{ImportContent, ImportReport, ImportedFile, ImportedModule, ImportedSummary},
+    },
+};
+
+use super::{
+    bindings_handler::{add_binding, read_version_bindings},
+    commands_handler::add_module,
+    git_handler::run_git_bytes,
+    repository_handler::get_current_repository,
+    workspace_handler::link_binding,
+};
+
+const STOW_IGNORED_NAMES: [&str; 6] = [
+    ".git",
+    ".gitignore",
+    ".gitmodules",
+    ".stow-local-ignore",
+    "RCS",
+    "CVS",
+];
+const STOW_IGNORED_TOP_LEVEL: [&str; 3] = ["README", "LICENSE", "COPYING"];
+const STOW_DOT_PREFIX: &str = "dot-";
+
+const CHEZMOI_ROOT_FILE: &str = ".chezmoiroot";
+const CHEZMOI_SPECIAL_PREFIX: &str = ".chezmoi";
+const CHEZMOI_HARMLESS_FILES: [&str; 2] = [CHEZMOI_ROOT_FILE, ".chezmoiversion"];
+const XDG_CONFIG_DIR: &str = ".config";
+
+const GIT_EXECUTABLE_MODE: &str = "100755";
+const GIT_SYMLINK_MODE: &str = "120000";
+const GIT_SUBMODULE_MODE: &str = "160000";
+const FILE_MODE: u32 = 0o644;
+const EXECUTABLE_MODE: u32 = 0o755;
+
+pub fn import_stow(stow_dir: &Path, dotfiles: bool, adopt: bool) -> Result<ImportReport> {
+    if !stow_dir.is_dir() {
+        return Err(ConfigsError::NotADirectory(stow_dir.to_path_buf()).into());
+    }
+
+    let mut packages = Vec::new();
+    for entry in fs::read_dir(stow_dir)? {
+        let path = entry?.path();
+        let is_hidden = path
+            .file_name()
+            .is_some_and(|el| el.to_string_lossy().starts_with('.'));
+        if path.is_dir() && !is_hidden {
+            packages.push(path);
+        }
+    }
+    packages.sort();
+
+    let modules = packages
+        .iter()
+        .map(|package| read_stow_package(package, dotfiles))
+        .collect::<Result<Vec<_>>>()?;
+    let versions = create_modules(&modules)?;
+
+    let mut report = ImportReport {
+        modules: summarize(&modules, &versions),
+        ..ImportReport::default()
+    };
+    if adopt {
+        for (package, version) in packages.iter().zip(&versions) {
+            adopt_stow_links(package, version, &mut report)?;
+        }
+    }
+    Ok(report)
+}
+
+pub fn import_chezmoi(source_dir: &Path, module_name: &Option<String>) -> Result<ImportReport> {
+    if !source_dir.is_dir() {
+        return Err(ConfigsError::NotADirectory(source_dir.to_path_buf()).into());
+    }
+    let root_file = source_dir.join(CHEZMOI_ROOT_FILE);
+    let source_dir = if root_file.is_file() {
+        source_dir.join(fs::read_to_string(&root_file)?.trim())
+    } else {
+        source_dir.to_path_buf()
+    };
+
+    let mut files = Vec::new();
+    let mut unsupported = Vec::new();
+    read_chezmoi_dir(
+        &source_dir,
+        &source_dir,
+        Path::new(""),
+        &mut files,
+        &mut unsupported,
+    )?;
+    let mappings: Vec<(PathBuf, String)> = module_name
+        .iter()
+        .map(|name| (PathBuf::new(), name.to_string()))
+        .collect();
+    let modules = group_files(files, &get_home_dir_path()?, &mappings)?;
+    let versions = create_modules(&modules)?;
+
+    Ok(ImportReport {
+        modules: summarize(&modules, &versions),
+        unsupported,
+        ..ImportReport::default()
+    })
+}
+
+pub fn import_bare_git(
+    git_dir: &Path,
+    work_tree: &Path,
+    mappings: &[String],
+) -> Result<ImportReport> {
+    if !git_dir.is_dir() {
+        return Err(ConfigsError::NotADirectory(git_dir.to_path_buf()).into());
+    }
+    let mappings = mappings
+        .iter()
+        .map(|mapping| match mapping.split_once('=') {
+            Some((prefix, name)) if !name.is_empty() => {
+                Ok((PathBuf::from(prefix), name.to_string()))
+            }
+            _ => Err(ConfigsError::InvalidModuleMapping(mapping.to_string()).into()),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let listing = run_git_bytes(git_dir, &["ls-tree", "-r", "-z", "--full-tree", "HEAD"])?;
+    let mut files = Vec::new();
+    let mut unsupported = Vec::new();
+    for entry in listing.split(|el| *el == 0).filter(|el| !el.is_empty()) {
+        let entry = String::from_utf8_lossy(entry);
+        let Some((metadata, path)) = entry.split_once('\t') else {
+            continue;
+        };
+        let Some((git_mode, _, object)) = metadata.split(' ').collect_tuple() else {
+            continue;
+        };
+
+        let external_path = work_tree.join(path);
+        match git_mode {
+            GIT_SUBMODULE_MODE => {
+                unsupported.push(format!("{}: submodule skipped", path));
+            }
+            GIT_SYMLINK_MODE => {
+                let link_target = run_git_bytes(git_dir, &["cat-file", "blob", object])?;
+                files.push(ImportedFile {
+                    external_path,
+                    content: ImportContent::Symlink(PathBuf::from(
+                        String::from_utf8_lossy(&link_target).to_string(),
+                    )),
+                    mode: None,
+                    deployment: Deployment::Copy,
+                });
+            }
+            _ => {
+                files.push(ImportedFile {
+                    external_path,
+                    content: ImportContent::Bytes(run_git_bytes(
+                        git_dir,
+                        &["cat-file", "blob", object],
+                    )?),
+                    mode: Some(if git_mode == GIT_EXECUTABLE_MODE {
+                        EXECUTABLE_MODE
+                    } else {
+                        FILE_MODE
+                    }),
+                    deployment: Deployment::Symlink,
+                });
+            }
+        }
+    }
+
+    let modules = group_files(files, work_tree, &mappings)?;
+    let versions = create_modules(&modules)?;
+    Ok(ImportReport {
+        modules: summarize(&modules, &versions),
+        unsupported,
+        ..ImportReport::default()
+    })
+}
+
+pub fn create_modules(modules: &[ImportedModule]) -> Result<Vec<Version>> {
+    let repository = get_current_repository()?;
+    for module in modules {
+        if repository.get_module(&module.name).is_ok() {
+            return Err(ConfigsError::ModuleAlreadyExists(module.name.to_string()).into());
+        }
+    }
+    modules.iter().map(create_module).collect()
+}
+
+pub fn summarize(modules: &[ImportedModule], versions: &[Version]) -> Vec<ImportedSummary> {
+    modules
+        .iter()
+        .zip(versions)
+        .map(|(module, version)| ImportedSummary {
+            module: module.name.to_string(),
+            config: version.name.to_string(),
+            files: module.files.len(),
+        })
+        .collect()
+}
+
+fn create_module(imported: &ImportedModule) -> Result<Version> {
+    let version = add_module(&imported.name)?.force_current_version()?;
+    let module_path = get_module_path(&version);
+
+    for file in &imported.files {
+        let internal_path = get_internal_path(&file.external_path)?;
+        let target_path = module_path.join(&internal_path);
+        if let Some(parent) = target_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        match &file.content {
+            ImportContent::File(source) => {
+                copy_preserving(source, &target_path, &IgnoreRules::default())?;
+            }
+            ImportContent::Bytes(bytes) => fs::write(&target_path, bytes)?,
+            ImportContent::Symlink(link_target) => symlink_auto(link_target, &target_path)?,
+        }
+        if let Some(mode) = file.mode {
+            set_mode(&target_path, mode)?;
+        }
+        add_binding(
+            &version,
+            &Binding {
+                internal_path,
+                external_path: file.external_path.to_path_buf(),
+                privileged: false,
+                mode: file.mode,
+                owner: None,
+                pattern: None,
+                deployment: file.deployment,
+                validator: None,
+            },
+        )?;
+    }
+    Ok(version)
+}
+
+fn read_stow_package(package_dir: &Path, dotfiles: bool) -> Result<ImportedModule> {
+    let home_path = get_home_dir_path()?;
+    let mut files = Vec::new();
+    let walker = WalkDir::new(package_dir)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| !is_ignored_by_stow(entry.path(), entry.depth()));
+    for entry in walker {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = entry.path().strip_prefix(package_dir)?;
+        let relative = if dotfiles {
+            expand_stow_dotfiles(relative)
+        } else {
+            relative.to_path_buf()
+        };
+        files.push(ImportedFile {
+            external_path: home_path.join(relative),
+            content: ImportContent::File(entry.path().to_path_buf()),
+            mode: get_mode(entry.path()),
+            deployment: Deployment::Symlink,
+        });
+    }
+    Ok(ImportedModule {
+        name: package_dir
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .to_string(),
+        files,
+    })
+}
+
+fn is_ignored_by_stow(path: &Path, depth: usize) -> bool {
+    let Some(name) = path.file_name().map(|el| el.to_string_lossy()) else {
+        return false;
+    };
+    STOW_IGNORED_NAMES.contains(&name.as_ref())
+        || name.ends_with('~')
+        || (depth == 1 && STOW_IGNORED_TOP_LEVEL.iter().any(|el| name.starts_with(el)))
+}
+
+fn expand_stow_dotfiles(path: &Path) -> PathBuf {
+    path.components()
+        .map(|component| match component {
+            Component::Normal(name) => {
+                let name = name.to_string_lossy();
+                match name.strip_prefix(STOW_DOT_PREFIX) {
+                    Some(rest) => format!(".{}", rest),
+                    None => name.to_string(),
+                }
+            }
+            other => other.as_os_str().to_string_lossy().to_string(),
+        })
+        .collect()
+}
+
+fn read_chezmoi_dir(
+    source_root: &Path,
+    dir: &Path,
+    target: &Path,
+    files: &mut Vec<ImportedFile>,
+    unsupported: &mut Vec<String>,
+) -> Result<()> {
+    let home_path = get_home_dir_path()?;
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|el| el.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort();
+
+    for path in entries {
+        let source_name = path.file_name().unwrap().to_string_lossy().to_string();
+        let relative = path
+            .strip_prefix(source_root)?
+            .to_string_lossy()
+            .to_string();
+        if source_name.starts_with('.') {
+            if source_name.starts_with(CHEZMOI_SPECIAL_PREFIX)
+                && !CHEZMOI_HARMLESS_FILES.contains(&source_name.as_str())
+            {
+                unsupported.push(format!("{}: special file skipped", relative));
+            }
+            continue;
+        }
+
+        let parsed = parse_name(&source_name);
+        if !parsed.skipped.is_empty() {
+            unsupported.push(format!(
+                "{}: {} skipped",
+                relative,
+                parsed.skipped.join(", ")
+            ));
+            continue;
+        }
+        if !parsed.ignored.is_empty() {
+            unsupported.push(format!(
+                "{}: {} ignored",
+                relative,
+                parsed.ignored.join(", ")
+            ));
+        }
+
+        let target_path = target.join(&parsed.name);
+        if fs::symlink_metadata(&path)?.is_dir() {
+            if parsed.private || parsed.readonly {
+                unsupported.push(format!("{}: directory permissions ignored", relative));
+            }
+            read_chezmoi_dir(source_root, &path, &target_path, files, unsupported)?;
+            continue;
+        }
+
+        let external_path = home_path.join(&target_path);
+        if parsed.symlink {
+            if parsed.template {
+                unsupported.push(format!("{}: templated symlink skipped", relative));
+                continue;
+            }
+            files.push(ImportedFile {
+                external_path,
+                content: ImportContent::Symlink(PathBuf::from(fs::read_to_string(&path)?.trim())),
+                mode: None,
+                deployment: Deployment::Copy,
+            });
+            continue;
+        }
+
+        // chezmoi removes the target of an empty source unless it is
+        // marked empty_, which has no counterpart in a binding.
+        if !parsed.empty && fs::metadata(&path)?.len() == 0 {
+            unsupported.push(format!(
+                "{}: empty file skipped, chezmoi removes its target",
+                relative
+            ));
+            continue;
+        }
+
+        let mut file = ImportedFile {
+            external_path,
+            content: ImportContent::File(path.to_path_buf()),
+            mode: Some(parsed.file_mode()),
+            deployment: Deployment::Symlink,
+        };
+        if parsed.template {
+            match convert_template(&fs::read_to_string(&path)?) {
+                Ok(converted) => {
+                    file.content = ImportContent::Bytes(converted.into_bytes());
+                    file.deployment = Deployment::Template;
+                }
+                Err(expressions) => unsupported.push(format!(
+                    "{}: template expressions {} kept verbatim",
+                    relative,
+                    expressions.join(", ")
+                )),
+            }
+        }
+        files.push(file);
+    }
+    Ok(())
+}
+
+fn group_files(
+    files: Vec<ImportedFile>,
+    root: &Path,
+    mappings: &[(PathBuf, String)],
+) -> Result<Vec<ImportedModule>> {
+    let mut groups: BTreeMap<String, Vec<ImportedFile>> = BTreeMap::new();
+    for file in files {
+        let relative = file.external_path.strip_prefix(root)?;
+        let name = match mappings
+            .iter()
+            .filter(|(prefix, _)| relative.starts_with(prefix))
+            .max_by_key(|(prefix, _)| prefix.components().count())
+        {
+            Some((_, name)) => name.to_string(),
+            None => get_group_name(relative),
+        };
+        groups.entry(name).or_default().push(file);
+    }
+    Ok(groups
+        .into_iter()
+        .map(|(name, files)| ImportedModule { name, files })
+        .collect())
+}
+
+fn get_group_name(relative: &Path) -> String {
+    let names: Vec<String> = relative
+        .components()
+        .map(|el| el.as_os_str().to_string_lossy().to_string())
+        .collect();
+    match names.as_slice() {
+        [first, second] if first == XDG_CONFIG_DIR => Path::new(second)
+            .file_stem()
+            .unwrap()
+            .to_string_lossy()
+            .to_string(),
+        [first, second, ..] if first == XDG_CONFIG_DIR => second.to_string(),
+        [first, ..] => first.trim_start_matches('.').to_string(),
+        [] => String::new(),
+    }
+}
+
+fn adopt_stow_links(
+    package_dir: &Path,
+    version: &Version,
+    report: &mut ImportReport,
+) -> Result<()> {
+    adopt_stow_bindings(
+        package_dir,
+        &get_home_dir_path()?,
+        &get_module_path(version),
+        &read_version_bindings(version)?.entries,
+        report,
+    )
+}
+
+fn adopt_stow_bindings(
+    package_dir: &Path,
+    home_path: &Path,
+    module_path: &Path,
+    bindings: &[Binding],
+    report: &mut ImportReport,
+) -> Result<()> {
+    let package_dir = package_dir.canonicalize()?;
+    for binding in bindings {
+        let external_path = &binding.external_path;
+        let parents: Vec<&Path> = external_path
+            .ancestors()
+            .skip(1)
+            .take_while(|el| el.starts_with(home_path) && *el != home_path)
+            .collect();
+        for parent in parents.into_iter().rev() {
+            if parent.is_symlink() && is_link_into(parent, &package_dir) {
+                fs::remove_file(parent)?;
+                fs::create_dir(parent)?;
+            }
+        }
+
+        let is_stow_link = external_path.is_symlink() && is_link_into(external_path, &package_dir);
+        if is_stow_link || !external_path.exists() {
+            // Unfolding leaves empty directories behind, so nested entries
+            // need their parents back.
+            if let Some(parent) = external_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            link_binding(binding, module_path, ConflictPolicy::Overwrite)?;
+            report.adopted.push(external_path.to_path_buf());
+        } else {
+            report.skipped.push(external_path.to_path_buf());
+        }
+    }
+    Ok(())
+}
+
+fn is_link_into(path: &Path, directory: &Path) -> bool {
+    path.canonicalize()
+        .is_ok_and(|target| target.starts_with(directory))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::test_helper::TempDir;
+
+    use super::*;
+
+    fn create_file(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn stow_packages_become_one_module_of_home_bindings() {
+        let temp_dir = TempDir::new("import-stow-read");
+        let package_dir = temp_dir.path().join("vim");
+        for file in [
+            "dot-vimrc",
+            "dot-config/nvim/init.lua",
+            "README.md",
+            ".git/config",
+            "dot-viminfo~",
+        ] {
+            create_file(&package_dir.join(file), file);
+        }
+
+        let module = read_stow_package(&package_dir, true).unwrap();
+
+        let home_path = get_home_dir_path().unwrap();
+        assert_eq!(module.name, "vim");
+        assert_eq!(
+            module
+                .files
+                .iter()
+                .map(|el| el.external_path.to_path_buf())
+                .collect_vec(),
+            vec![
+                home_path.join(".config/nvim/init.lua"),
+                home_path.join(".vimrc")
+            ]
+        );
+        assert!(module
+            .files
+            .iter()
+            .all(|el| el.deployment == Deployment::Symlink));
+    }
+
+    #[test]
+    fn empty_chezmoi_sources_are_kept_only_when_marked() {
+        let temp_dir = TempDir::new("import-chezmoi-empty");
+        let source_dir = temp_dir.path();
+        create_file(&source_dir.join("dot_removed"), "");
+        create_file(&source_dir.join("empty_dot_hushlogin"), "");
+        create_file(&source_dir.join("dot_profile"), "export A=1");
+
+        let mut files = Vec::new();
+        let mut unsupported = Vec::new();
+        read_chezmoi_dir(
+            source_dir,
+            source_dir,
+            Path::new(""),
+            &mut files,
+            &mut unsupported,
+        )
+        .unwrap();
+
+        let home_path = get_home_dir_path().unwrap();
+        assert_eq!(
+            files
+                .iter()
+                .map(|el| el.external_path.to_path_buf())
+                .collect_vec(),
+            vec![home_path.join(".profile"), home_path.join(".hushlogin")]
+        );
+        assert_eq!(
+            unsupported,
+            vec!["dot_removed: empty file skipped, chezmoi removes its target"]
+        );
+    }
+
+    #[test]
+    fn files_are_grouped_by_top_level_entry_and_mapping() {
+        let root = Path::new("/home/user");
+        let file = |path: &str| ImportedFile {
+            external_path: root.join(path),
+            content: ImportContent::Bytes(Vec::new()),
+            mode: None,
+            deployment: Deployment::Symlink,
+        };
+        let files = vec![
+            file(".zshrc"),
+            file(".config/nvim/init.lua"),
+            file(".config/nvim/lua/plugins.lua"),
+            file(".config/starship.toml"),
+            file(".config/git/config"),
+        ];
+        let mappings = vec![(PathBuf::from(".config/git"), "vcs".to_string())];
+
+        let modules = group_files(files, root, &mappings).unwrap();
+
+        assert_eq!(
+            modules
+                .iter()
+                .map(|el| (el.name.as_str(), el.files.len()))
+                .collect_vec(),
+            vec![("nvim", 2), ("starship", 1), ("vcs", 1), ("zshrc", 1)]
+        );
+    }
+
+    #[test]
+    fn adopting_unfolds_folded_stow_directories() {
+        let temp_dir = TempDir::new("import-stow-adopt");
+        let root = temp_dir.path();
+        let home_path = root.join("home");
+        let package_dir = root.join("stow/nvim");
+        let module_path = root.join("repository/nvim");
+        create_file(&package_dir.join(".config/nvim/init.lua"), "stow");
+        create_file(&package_dir.join(".config/nvim/lua/plugins.lua"), "stow");
+        create_file(&module_path.join(".config/nvim/init.lua"), "module");
+        create_file(&module_path.join(".config/nvim/lua/plugins.lua"), "module");
+        create_file(&module_path.join(".gitconfig"), "module");
+        create_file(&home_path.join(".gitconfig"), "own");
+        fs::create_dir_all(home_path.join(".config")).unwrap();
+        symlink_auto(
+            package_dir.join(".config/nvim"),
+            home_path.join(".config/nvim"),
+        )
+        .unwrap();
+        let bindings = [
+            ".config/nvim/init.lua",
+            ".config/nvim/lua/plugins.lua",
+            ".gitconfig",
+        ]
+        .map(|el| Binding {
+            internal_path: PathBuf::from(el),
+            external_path: home_path.join(el),
+            ..Default::default()
+        });
+
+        let mut report = ImportReport::default();
+        adopt_stow_bindings(
+            &package_dir,
+            &home_path,
+            &module_path,
+            &bindings,
+            &mut report,
+        )
+        .unwrap();
+
+        let nvim_path = home_path.join(".config/nvim");
+        assert!(nvim_path.is_dir() && !nvim_path.is_symlink());
+        assert_eq!(
+            fs::read_link(nvim_path.join("lua/plugins.lua")).unwrap(),
+            module_path.join(".config/nvim/lua/plugins.lua")
+        );
+        assert_eq!(
+            fs::read_to_string(nvim_path.join("init.lua")).unwrap(),
+            "module"
+        );
+        assert_eq!(
+            report.adopted,
+            vec![
+                home_path.join(".config/nvim/init.lua"),
+                home_path.join(".config/nvim/lua/plugins.lua")
+            ]
+        );
+        assert_eq!(report.skipped, vec![home_path.join(".gitconfig")]);
+        assert_eq!(
+            fs::read_to_string(home_path.join(".gitconfig")).unwrap(),
+            "own"
+        );
+    }
+}
@@ -1,4 +1,20 @@
+pub mod backup_handler;
 pub mod bindings_handler;
+pub mod bootstrap_handler;
+pub mod bundle_handler;
 pub mod commands_handler;
+pub mod edit_handler;
+pub mod eject_handler;
+pub mod env_handler;
+pub mod exec_handler;
+pub mod git_handler;
+pub mod history_handler;
+pub mod hooks_handler;
+pub mod import_handler;
+pub mod picker_handler;
 pub mod repository_handler;
+pub mod scope_handler;
+pub mod settings_handler;
+pub mod sync_handler;
+pub mod watch_handler;
 pub mod workspace_handler;
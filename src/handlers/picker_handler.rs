@@ -0,0 +1,365 @@
+use anyhow::Result;
+use ratatui::{
+    backend::Backend,
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, List, ListItem, ListState, Paragraph},
+    Frame, Terminal,
+};
+
+use crate::{
+    common::paths_helper::get_display_path,
+    types::{
+        bindings::Binding,
+        history::Selection,
+        picker::{BindingChange, PickerEntry},
+    },
+};
+
+use super::{bindings_handler::read_version_bindings, repository_handler::get_current_repository};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PickerAction {
+    Continue,
+    Cancel,
+    Select(Selection),
+}
+
+pub struct Picker {
+    entries: Vec<PickerEntry>,
+    filter: String,
+    list_state: ListState,
+}
+
+impl Picker {
+    pub fn new(entries: Vec<PickerEntry>) -> Self {
+        let selected = entries.iter().position(|el| el.current).unwrap_or(0);
+        Picker {
+            entries,
+            filter: String::new(),
+            list_state: ListState::default().with_selected(Some(selected)),
+        }
+    }
+
+    pub fn visible_entries(&self) -> Vec<&PickerEntry> {
+        let filter = self.filter.to_lowercase();
+        self.entries
+            .iter()
+            .filter(|el| el.label().to_lowercase().contains(&filter))
+            .collect()
+    }
+
+    pub fn highlighted(&self) -> Option<&PickerEntry> {
+        let visible = self.visible_entries();
+        self.list_state
+            .selected()
+            .and_then(|index| visible.get(index).copied())
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> PickerAction {
+        if key.kind != KeyEventKind::Press {
+            return PickerAction::Continue;
+        }
+        match key.code {
+            KeyCode::Esc => return PickerAction::Cancel,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return PickerAction::Cancel;
+            }
+            KeyCode::Enter => {
+                if let Some(entry) = self.highlighted() {
+                    return PickerAction::Select(Selection {
+                        module: entry.module.to_string(),
+                        config: entry.config.to_string(),
+                    });
+                }
+            }
+            KeyCode::Up => self.move_highlight(-1),
+            KeyCode::Down => self.move_highlight(1),
+            KeyCode::Backspace => {
+                self.filter.pop();
+                self.reset_highlight();
+            }
+            KeyCode::Char(character) => {
+                self.filter.push(character);
+                self.reset_highlight();
+            }
+            _ => {}
+        }
+        PickerAction::Continue
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame) {
+        let [main_area, filter_area] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [list_area, preview_area] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(main_area);
+        let [bindings_area, diff_area] =
+            Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(preview_area);
+
+        let items: Vec<ListItem> = self
+            .visible_entries()
+            .into_iter()
+            .map(|entry| {
+                let label = if entry.current {
+                    Span::from(entry.label()).bold().underlined()
+                } else {
+                    Span::from(entry.label())
+                };
+                ListItem::new(label)
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::bordered().title("Configs"))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("> ");
+        frame.render_stateful_widget(list, list_area, &mut self.list_state);
+
+        let (bindings, diff) = match self.highlighted() {
+            Some(entry) => (binding_lines(&entry.bindings), diff_lines(entry)),
+            None => (Vec::new(), Vec::new()),
+        };
+        frame.render_widget(
+            Paragraph::new(bindings).block(Block::bordered().title("Bindings")),
+            bindings_area,
+        );
+        frame.render_widget(
+            Paragraph::new(diff).block(Block::bordered().title("Changes")),
+            diff_area,
+        );
+
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::from("Filter: ").dim(),
+                Span::from(self.filter.to_string()),
+            ])),
+            filter_area,
+        );
+    }
+
+    fn move_highlight(&mut self, offset: isize) {
+        let count = self.visible_entries().len();
+        if count == 0 {
+            return;
+        }
+        let current = self.list_state.selected().unwrap_or(0);
+        let next = current.saturating_add_signed(offset).min(count - 1);
+        self.list_state.select(Some(next));
+    }
+
+    fn reset_highlight(&mut self) {
+        let selected = if self.visible_entries().is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+        self.list_state.select(selected);
+    }
+}
+
+pub fn pick_config() -> Result<Option<Selection>> {
+    let entries = get_picker_entries()?;
+    if entries.is_empty() {
+        return Ok(None);
+    }
+    let mut picker = Picker::new(entries);
+    let mut terminal = ratatui::try_init()?;
+    let result = run_picker(&mut terminal, &mut picker, || Ok(event::read()?));
+    ratatui::restore();
+    result
+}
+
+pub fn run_picker<B: Backend>(
+    terminal: &mut Terminal<B>,
+    picker: &mut Picker,
+    mut next_event: impl FnMut() -> Result<Event>,
+) -> Result<Option<Selection>> {
+    loop {
+        terminal.draw(|frame| picker.draw(frame))?;
+        if let Event::Key(key) = next_event()? {
+            match picker.handle_key(key) {
+                PickerAction::Continue => {}
+                PickerAction::Cancel => return Ok(None),
+                PickerAction::Select(selection) => return Ok(Some(selection)),
+            }
+        }
+    }
+}
+
+pub fn get_picker_entries() -> Result<Vec<PickerEntry>> {
+    let repository = get_current_repository()?;
+    let mut entries = Vec::new();
+    for module in repository.list_modules()? {
+        let current_bindings = match &module.current_version {
+            Some(version) => Some(read_version_bindings(version)?.entries),
+            None => None,
+        };
+        for version in &module.versions {
+            entries.push(PickerEntry {
+                module: module.name.to_string(),
+                config: version.name.to_string(),
+                current: module
+                    .current_version
+                    .as_ref()
+                    .is_some_and(|el| el.name == version.name),
+                bindings: read_version_bindings(version)?.entries,
+                current_bindings: current_bindings.to_owned(),
+            });
+        }
+    }
+    Ok(entries)
+}
+
+pub fn get_binding_changes(entry: &PickerEntry) -> Vec<(BindingChange, Binding)> {
+    let current_bindings = entry.current_bindings.to_owned().unwrap_or_default();
+    let mut changes = Vec::new();
+    for binding in &entry.bindings {
+        match current_bindings
+            .iter()
+            .find(|el| el.external_path == binding.external_path)
+        {
+            None => changes.push((BindingChange::Added, binding.to_owned())),
+            Some(current) if current != binding => {
+                changes.push((BindingChange::Changed, binding.to_owned()));
+            }
+            Some(_) => {}
+        }
+    }
+    for binding in &current_bindings {
+        if !entry
+            .bindings
+            .iter()
+            .any(|el| el.external_path == binding.external_path)
+        {
+            changes.push((BindingChange::Removed, binding.to_owned()));
+        }
+    }
+    changes
+}
+
+fn binding_lines(bindings: &[Binding]) -> Vec<Line<'static>> {
+    if bindings.is_empty() {
+        return vec![Line::from("No bindings.").dim()];
+    }
+    bindings
+        .iter()
+        .map(|binding| {
+            Line::from(vec![
+                Span::from(binding.internal_path.to_string_lossy().to_string()).magenta(),
+                Span::from(" -> ").dim(),
+                Span::from(get_display_path(&binding.external_path)).cyan(),
+            ])
+        })
+        .collect()
+}
+
+fn diff_lines(entry: &PickerEntry) -> Vec<Line<'static>> {
+    if entry.current {
+        return vec![Line::from("Currently selected.").dim()];
+    }
+    let changes = get_binding_changes(entry);
+    if changes.is_empty() {
+        return vec![Line::from("No changes.").dim()];
+    }
+    changes
+        .into_iter()
+        .map(|(change, binding)| {
+            let (symbol, color) = match change {
+                BindingChange::Added => ("+", Color::Green),
+                BindingChange::Removed => ("-", Color::Red),
+                BindingChange::Changed => ("~", Color::Yellow),
+            };
+            Line::from(format!(
+                "{} {}",
+                symbol,
+                get_display_path(&binding.external_path)
+            ))
+            .fg(color)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use ratatui::backend::TestBackend;
+
+    use super::*;
+
+    fn entry(module: &str, config: &str, current: bool) -> PickerEntry {
+        PickerEntry {
+            module: module.to_string(),
+            config: config.to_string(),
+            current,
+            bindings: vec![Binding {
+                internal_path: PathBuf::from("init.lua"),
+                external_path: PathBuf::from(format!("/home/user/.config/{}/init.lua", module)),
+                ..Default::default()
+            }],
+            current_bindings: None,
+        }
+    }
+
+    fn run(keys: &[KeyCode]) -> (Option<Selection>, String) {
+        let mut terminal = Terminal::new(TestBackend::new(80, 12)).unwrap();
+        let mut picker = Picker::new(vec![
+            entry("nvim", "default", true),
+            entry("nvim", "minimal", false),
+            entry("zsh", "work", false),
+        ]);
+        let mut events = keys
+            .iter()
+            .map(|el| Event::Key(KeyEvent::from(*el)))
+            .collect::<Vec<_>>()
+            .into_iter();
+        let selection = run_picker(&mut terminal, &mut picker, || {
+            Ok(events.next().expect("picker asked for more events"))
+        })
+        .unwrap();
+        let screen = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|el| el.symbol())
+            .collect();
+        (selection, screen)
+    }
+
+    #[test]
+    fn selects_the_filtered_config() {
+        let keys = [
+            KeyCode::Char('m'),
+            KeyCode::Char('i'),
+            KeyCode::Char('n'),
+            KeyCode::Enter,
+        ];
+        let (selection, screen) = run(&keys);
+        assert_eq!(
+            selection,
+            Some(Selection {
+                module: "nvim".to_string(),
+                config: "minimal".to_string(),
+            })
+        );
+        assert!(screen.contains("Filter: min"));
+        assert!(!screen.contains("zsh/work"));
+    }
+
+    #[test]
+    fn starts_on_the_current_config() {
+        let (selection, screen) = run(&[KeyCode::Down, KeyCode::Down, KeyCode::Enter]);
+        assert_eq!(selection.unwrap().config, "work");
+        assert!(screen.contains("nvim/default"));
+    }
+
+    #[test]
+    fn cancels_on_escape() {
+        let (selection, _) = run(&[KeyCode::Down, KeyCode::Esc]);
+        assert_eq!(selection, None);
+    }
+}
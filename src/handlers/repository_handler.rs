@@ -1,10 +1,13 @@
-use std::env;
+use std::{env, path::Path};
 
 use anyhow::Result;
 use versions::{open, Repository};
 
 pub fn get_current_repository() -> Result<Repository> {
-    let current_dir = env::current_dir()?;
-    let repository = open(&current_dir, true)?;
+    get_repository(&env::current_dir()?)
+}
+
+pub fn get_repository(path: &Path) -> Result<Repository> {
+    let repository = open(path, true)?;
     Ok(repository)
 }
@@ -0,0 +1,258 @@
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    os::unix::process,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use commons::utils::file_util::{read_file, write_file};
+use versions::Repository;
+
+use crate::{
+    common::{
+        constants,
+        state_helper::get_state_dir,
+        versions_helper::{find_module, find_version},
+    },
+    types::{
+        conflicts::ConflictPolicy,
+        errors::ConfigsError,
+        scopes::{PreviousSelection, ScopeFrame},
+        shells::ScriptShell,
+    },
+};
+
+use super::{
+    commands_handler::{deselect_module, select_config},
+    eject_handler::is_ejected,
+    repository_handler::get_repository,
+};
+
+#[derive(Debug, Default)]
+pub struct ScopeChange {
+    pub left: Vec<ScopeFrame>,
+    pub entered: Vec<ScopeFrame>,
+}
+
+pub fn apply_scopes(session: &str, directory: &Path) -> Result<ScopeChange> {
+    let stack_path = get_stack_path(session)?;
+    let scopes = find_scopes(directory)?;
+    let mut stack = read_stack(&stack_path)?;
+    let unchanged = stack
+        .iter()
+        .zip(&scopes)
+        .take_while(|(frame, (scope_dir, selections))| {
+            &frame.directory == scope_dir && &frame.selections == selections
+        })
+        .count();
+
+    let mut change = ScopeChange::default();
+    if unchanged == stack.len() && unchanged == scopes.len() {
+        return Ok(change);
+    }
+
+    while stack.len() > unchanged {
+        let frame = stack.pop().unwrap();
+        restore_frame(&frame)?;
+        write_stack(&stack_path, &stack)?;
+        change.left.push(frame);
+    }
+    for (scope_dir, selections) in scopes.into_iter().skip(unchanged) {
+        let frame = enter_scope(&scope_dir, selections)?;
+        stack.push(frame.to_owned());
+        write_stack(&stack_path, &stack)?;
+        change.entered.push(frame);
+    }
+    Ok(change)
+}
+
+pub fn get_session() -> String {
+    env::var(constants::SESSION_VARIABLE)
+        .ok()
+        .filter(|el| is_valid_session(el))
+        .unwrap_or_else(|| process::parent_id().to_string())
+}
+
+pub fn get_hook_script(shell: ScriptShell) -> String {
+    let script = match shell {
+        ScriptShell::Zsh => {
+            "export CONFIGS_SESSION=$$\n\
+             _configs_hook() {\n  configs apply --quiet\n}\n\
+             typeset -ag chpwd_functions\n\
+             if (( ! ${chpwd_functions[(I)_configs_hook]} )); then\n  chpwd_functions+=(_configs_hook)\nfi\n\
+             _configs_hook"
+        }
+        ScriptShell::Bash => {
+            "export CONFIGS_SESSION=$$\n\
+             _configs_hook() {\n  if [[ \"$PWD\" != \"$_CONFIGS_LAST_PWD\" ]]; then\n    _CONFIGS_LAST_PWD=\"$PWD\"\n    configs apply --quiet\n  fi\n}\n\
+             if [[ \";${PROMPT_COMMAND:-};\" != *\";_configs_hook;\"* ]]; then\n  PROMPT_COMMAND=\"_configs_hook${PROMPT_COMMAND:+;$PROMPT_COMMAND}\"\nfi"
+        }
+        ScriptShell::Fish => {
+            "set -gx CONFIGS_SESSION $fish_pid\n\
+             function _configs_hook --on-variable PWD\n  configs apply --quiet\nend\n\
+             _configs_hook"
+        }
+    };
+    script.to_string()
+}
+
+fn find_scopes(directory: &Path) -> Result<Vec<(PathBuf, BTreeMap<String, String>)>> {
+    let mut scopes = Vec::new();
+    for ancestor in directory.ancestors() {
+        let scope_file = ancestor.join(constants::SCOPE_FILE);
+        if !scope_file.is_file() {
+            continue;
+        }
+        let content = read_file(&scope_file)?;
+        let selections = if content.trim().is_empty() {
+            BTreeMap::new()
+        } else {
+            serde_yml::from_str(&content)?
+        };
+        scopes.push((ancestor.to_path_buf(), selections));
+    }
+    scopes.reverse();
+    Ok(scopes)
+}
+
+fn enter_scope(scope_dir: &Path, selections: BTreeMap<String, String>) -> Result<ScopeFrame> {
+    let repository = get_repository(scope_dir)?;
+    let mut frame = ScopeFrame {
+        directory: scope_dir.to_path_buf(),
+        repository: repository.root_path.to_path_buf(),
+        selections: BTreeMap::new(),
+        previous: Vec::new(),
+        previous_module: repository.current_module()?.map(|el| el.name),
+    };
+
+    for (module_name, config) in &selections {
+        match select_scoped(&repository, module_name, config) {
+            Ok(Some(previous)) => frame.previous.push(previous),
+            Ok(None) => {}
+            Err(error) => {
+                restore_frame(&frame)?;
+                return Err(error);
+            }
+        }
+    }
+    frame.selections = selections;
+    Ok(frame)
+}
+
+fn select_scoped(
+    repository: &Repository,
+    module_name: &str,
+    config: &str,
+) -> Result<Option<PreviousSelection>> {
+    let module = find_module(repository, module_name)?;
+    if is_ejected(&repository.root_path, &module.name)? {
+        return Err(ConfigsError::ModuleEjected(module.name).into());
+    }
+    let config = find_version(&module, config)?.name;
+    let current_config = module.current_version.map(|el| el.name);
+    if current_config.as_deref() == Some(config.as_str()) {
+        return Ok(None);
+    }
+    select_config(repository, &module.name, &config, ConflictPolicy::Abort)?;
+    Ok(Some(PreviousSelection {
+        module: module.name,
+        config: current_config,
+    }))
+}
+
+fn restore_frame(frame: &ScopeFrame) -> Result<()> {
+    let repository = get_repository(&frame.repository)?;
+    for previous in frame.previous.iter().rev() {
+        match &previous.config {
+            Some(config) => {
+                select_config(
+                    &repository,
+                    &previous.module,
+                    config,
+                    ConflictPolicy::Backup,
+                )?;
+            }
+            None => {
+                let module = repository.get_module(&previous.module)?;
+                deselect_module(&repository, &module)?;
+            }
+        }
+    }
+
+    let previous_module = match &frame.previous_module {
+        Some(name) => Some(repository.get_module(name)?),
+        None => None,
+    };
+    repository.select_module(&previous_module)?;
+    Ok(())
+}
+
+fn is_valid_session(session: &str) -> bool {
+    !session.is_empty() && session.chars().all(|el| el.is_ascii_alphanumeric())
+}
+
+fn get_stack_path(session: &str) -> Result<PathBuf> {
+    Ok(get_state_dir()?
+        .join(constants::SCOPES_DIR)
+        .join(format!("{}.yml", session)))
+}
+
+fn read_stack(stack_path: &Path) -> Result<Vec<ScopeFrame>> {
+    if !stack_path.is_file() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_yml::from_str(&read_file(stack_path)?)?)
+}
+
+fn write_stack(stack_path: &Path, stack: &[ScopeFrame]) -> Result<()> {
+    if stack.is_empty() {
+        if stack_path.is_file() {
+            fs::remove_file(stack_path)?;
+        }
+        return Ok(());
+    }
+    fs::create_dir_all(stack_path.parent().unwrap())?;
+    write_file(stack_path, &serde_yml::to_string(stack)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_helper::TempDir;
+
+    #[test]
+    fn keeps_a_stack_per_session() {
+        let temp_dir = TempDir::new("scope-stack");
+        let first_path = temp_dir.path().join("101.yml");
+        let second_path = temp_dir.path().join("202.yml");
+        let frame = ScopeFrame {
+            directory: PathBuf::from("/project"),
+            repository: PathBuf::from("/repository"),
+            selections: BTreeMap::new(),
+            previous: Vec::new(),
+            previous_module: None,
+        };
+        write_stack(&first_path, &[frame]).unwrap();
+        assert_eq!(read_stack(&first_path).unwrap().len(), 1);
+        assert!(read_stack(&second_path).unwrap().is_empty());
+
+        write_stack(&first_path, &[]).unwrap();
+        assert!(!first_path.exists());
+
+        assert!(get_stack_path("101").unwrap().ends_with("101.yml"));
+        assert_ne!(
+            get_stack_path("101").unwrap(),
+            get_stack_path("202").unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_sessions_escaping_the_scopes_dir() {
+        assert!(is_valid_session("101"));
+        assert!(!is_valid_session(""));
+        assert!(!is_valid_session("../101"));
+        assert!(!is_valid_session("101/202"));
+    }
+}
@@ -0,0 +1,22 @@
+use std::path::Path;
+
+use anyhow::Result;
+use commons::utils::file_util::{read_file, write_file};
+
+use crate::{common::constants, types::settings::Settings};
+
+pub fn read_settings(repository_path: &Path) -> Result<Settings> {
+    let settings_path = repository_path.join(constants::SETTINGS_FILE);
+    if !settings_path.is_file() {
+        return Ok(Settings::default());
+    }
+    Ok(serde_yml::from_str(&read_file(&settings_path)?)?)
+}
+
+pub fn update_settings(repository_path: &Path, updater: impl Fn(&mut Settings)) -> Result<()> {
+    let mut settings = read_settings(repository_path)?;
+    updater(&mut settings);
+    let settings_path = repository_path.join(constants::SETTINGS_FILE);
+    write_file(&settings_path, &serde_yml::to_string(&settings)?)?;
+    Ok(())
+}
@@ -0,0 +1,357 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use itertools::Itertools;
+
+use crate::{
+    common::versions_helper::get_module_path,
+    types::{
+        bindings::{Binding, Bindings},
+        conflicts::ConflictPolicy,
+        errors::ConfigsError,
+        status::BindingState,
+    },
+};
+
+use super::{
+    bindings_handler::read_bindings,
+    eject_handler::is_ejected,
+    git_handler::{commit_paths, get_changed_files, get_head, run_git},
+    repository_handler::get_current_repository,
+    workspace_handler::{get_binding_status, link_binding, unlink_binding},
+};
+
+#[derive(Clone, Default, Debug)]
+pub struct SyncReport {
+    pub committed: bool,
+    pub changed_files: Vec<PathBuf>,
+    pub linked: Vec<Binding>,
+    pub unlinked: Vec<Binding>,
+    pub relinked: Vec<Binding>,
+}
+
+struct SelectedConfig {
+    module: String,
+    config: String,
+    module_path: PathBuf,
+}
+
+pub fn sync_repository(remote: &str, branch: &str) -> Result<SyncReport> {
+    let repository = get_current_repository()?;
+    sync_selected_configs(
+        &repository.root_path,
+        &get_selected_configs()?,
+        remote,
+        branch,
+    )
+}
+
+pub fn get_diverged_paths() -> Result<Vec<PathBuf>> {
+    let repository = get_current_repository()?;
+    find_diverged_paths(&repository.root_path, &get_selected_configs()?)
+}
+
+fn get_selected_configs() -> Result<Vec<SelectedConfig>> {
+    let repository = get_current_repository()?;
+    let mut selected = Vec::new();
+    for module in repository.list_modules()? {
+        let Some(version) = &module.current_version else {
+            continue;
+        };
+        if is_ejected(&repository.root_path, &module.name)? {
+            continue;
+        }
+        selected.push(SelectedConfig {
+            module: module.name.to_string(),
+            config: version.name.to_string(),
+            module_path: get_module_path(version),
+        });
+    }
+    Ok(selected)
+}
+
+fn sync_selected_configs(
+    repository_path: &Path,
+    selected: &[SelectedConfig],
+    remote: &str,
+    branch: &str,
+) -> Result<SyncReport> {
+    let diverged = find_diverged_paths(repository_path, selected)?;
+    if !diverged.is_empty() {
+        return Err(ConfigsError::DeployedFilesDiverged(diverged).into());
+    }
+
+    let mut report = SyncReport {
+        committed: commit_paths(
+            repository_path,
+            &[repository_path.to_path_buf()],
+            "sync local changes",
+        )?,
+        ..Default::default()
+    };
+
+    let bindings_before = read_bindings(repository_path).unwrap_or_default();
+    let head_before = get_head(repository_path)?;
+    if let Err(error) = run_git(repository_path, &["pull", "--rebase", remote, branch]) {
+        let _ = run_git(repository_path, &["rebase", "--abort"]);
+        return Err(error);
+    }
+    let head_after = get_head(repository_path)?;
+
+    if head_before != head_after {
+        report.changed_files = get_changed_files(repository_path, &head_before, &head_after)?;
+        relink_changes(repository_path, selected, &bindings_before, &mut report)?;
+    }
+
+    run_git(
+        repository_path,
+        &["push", remote, &format!("HEAD:{}", branch)],
+    )?;
+    Ok(report)
+}
+
+fn get_entries(bindings: &Bindings, selected: &SelectedConfig) -> Vec<Binding> {
+    bindings
+        .module_bindings
+        .get(&selected.module)
+        .and_then(|el| el.version_bindings.get(&selected.config))
+        .map(|el| el.entries.to_owned())
+        .unwrap_or_default()
+}
+
+fn find_diverged_paths(
+    repository_path: &Path,
+    selected: &[SelectedConfig],
+) -> Result<Vec<PathBuf>> {
+    if selected.is_empty() {
+        return Ok(Vec::new());
+    }
+    let bindings = read_bindings(repository_path)?;
+    let mut diverged = Vec::new();
+    for config in selected {
+        for binding in get_entries(&bindings, config) {
+            let status = get_binding_status(&binding, &config.module_path)?;
+            if status.state != BindingState::Linked {
+                diverged.push(status.binding.external_path);
+            }
+        }
+    }
+    Ok(diverged)
+}
+
+fn relink_changes(
+    repository_path: &Path,
+    selected: &[SelectedConfig],
+    bindings_before: &Bindings,
+    report: &mut SyncReport,
+) -> Result<()> {
+    let bindings_after = read_bindings(repository_path).unwrap_or_default();
+
+    for config in selected {
+        let module_path = &config.module_path;
+        let old_entries = get_entries(bindings_before, config);
+        let new_entries = get_entries(&bindings_after, config);
+
+        for binding in old_entries.iter().filter(|el| !new_entries.contains(el)) {
+            if get_binding_status(binding, module_path)?.state == BindingState::Linked {
+                unlink_binding(binding, module_path)?;
+                report.unlinked.push(binding.to_owned());
+            }
+        }
+
+        let module_dir = module_path
+            .strip_prefix(repository_path)
+            .unwrap_or(module_path);
+        let changed_files = report
+            .changed_files
+            .iter()
+            .filter_map(|el| el.strip_prefix(module_dir).ok())
+            .collect_vec();
+        for binding in &new_entries {
+            if !old_entries.contains(binding) {
+                // A changed binding left its previous content in place above.
+                let previously_managed = old_entries
+                    .iter()
+                    .any(|el| el.external_path == binding.external_path);
+                let policy = if previously_managed {
+                    ConflictPolicy::Overwrite
+                } else {
+                    ConflictPolicy::Abort
+                };
+                link_binding(binding, module_path, policy)?;
+                report.linked.push(binding.to_owned());
+            } else if changed_files
+                .iter()
+                .any(|el| el.starts_with(&binding.internal_path))
+            {
+                link_binding(binding, module_path, ConflictPolicy::Overwrite)?;
+                report.relinked.push(binding.to_owned());
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs};
+
+    use crate::{
+        common::test_helper::TempDir,
+        handlers::bindings_handler::write_bindings,
+        types::bindings::{Deployment, ModuleBindings, VersionBindings},
+    };
+
+    use super::*;
+
+    struct Remote {
+        local_path: PathBuf,
+        other_path: PathBuf,
+        remote_path: PathBuf,
+        binding: Binding,
+        selected: SelectedConfig,
+    }
+
+    fn init_clone(path: &Path) {
+        run_git(path, &["config", "user.name", "tester"]).unwrap();
+        run_git(path, &["config", "user.email", "tester@localhost"]).unwrap();
+    }
+
+    fn create_remote(root: &Path) -> Remote {
+        let remote_path = root.join("remote.git");
+        let local_path = root.join("local");
+        let other_path = root.join("other");
+        fs::create_dir_all(&remote_path).unwrap();
+        run_git(&remote_path, &["init", "--quiet", "--bare", "-b", "main"]).unwrap();
+
+        fs::create_dir_all(local_path.join("vim")).unwrap();
+        run_git(&local_path, &["init", "--quiet", "-b", "main"]).unwrap();
+        init_clone(&local_path);
+        run_git(
+            &local_path,
+            &["remote", "add", "origin", &remote_path.to_string_lossy()],
+        )
+        .unwrap();
+        fs::write(local_path.join("vim/vimrc"), "set number").unwrap();
+        let binding = Binding {
+            internal_path: PathBuf::from("vimrc"),
+            external_path: root.join("home/.vimrc"),
+            deployment: Deployment::Copy,
+            ..Default::default()
+        };
+        let bindings = Bindings {
+            module_bindings: HashMap::from([(
+                "vim".to_string(),
+                ModuleBindings {
+                    version_bindings: HashMap::from([(
+                        "default".to_string(),
+                        VersionBindings {
+                            entries: vec![binding.to_owned()],
+                            ..Default::default()
+                        },
+                    )]),
+                },
+            )]),
+        };
+        write_bindings(&local_path, &bindings).unwrap();
+        commit_paths(&local_path, &[local_path.to_path_buf()], "initial").unwrap();
+        run_git(&local_path, &["push", "--quiet", "origin", "HEAD:main"]).unwrap();
+
+        run_git(
+            root,
+            &[
+                "clone",
+                "--quiet",
+                "-b",
+                "main",
+                &remote_path.to_string_lossy(),
+                "other",
+            ],
+        )
+        .unwrap();
+        init_clone(&other_path);
+
+        let selected = SelectedConfig {
+            module: "vim".to_string(),
+            config: "default".to_string(),
+            module_path: local_path.join("vim"),
+        };
+        fs::create_dir_all(root.join("home")).unwrap();
+        link_binding(&binding, &selected.module_path, ConflictPolicy::Abort).unwrap();
+
+        Remote {
+            local_path,
+            other_path,
+            remote_path,
+            binding,
+            selected,
+        }
+    }
+
+    fn push_change(repository_path: &Path, file: &str, content: &str) {
+        fs::write(repository_path.join(file), content).unwrap();
+        commit_paths(repository_path, &[repository_path.to_path_buf()], "change").unwrap();
+        run_git(repository_path, &["push", "--quiet", "origin", "HEAD:main"]).unwrap();
+    }
+
+    #[test]
+    fn syncing_pulls_and_relinks_changed_bindings() {
+        let temp_dir = TempDir::new("sync-pull");
+        let remote = create_remote(temp_dir.path());
+        push_change(&remote.other_path, "vim/vimrc", "set relativenumber");
+
+        let report =
+            sync_selected_configs(&remote.local_path, &[remote.selected], "origin", "main")
+                .unwrap();
+
+        assert_eq!(report.changed_files, vec![PathBuf::from("vim/vimrc")]);
+        assert_eq!(report.relinked, vec![remote.binding.to_owned()]);
+        assert_eq!(
+            fs::read_to_string(&remote.binding.external_path).unwrap(),
+            "set relativenumber"
+        );
+    }
+
+    #[test]
+    fn syncing_refuses_when_deployed_files_diverged() {
+        let temp_dir = TempDir::new("sync-diverged");
+        let remote = create_remote(temp_dir.path());
+        push_change(&remote.other_path, "vim/vimrc", "set relativenumber");
+        let head_before = get_head(&remote.local_path).unwrap();
+        fs::write(&remote.binding.external_path, "set paste").unwrap();
+
+        let error = sync_selected_configs(&remote.local_path, &[remote.selected], "origin", "main")
+            .unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<ConfigsError>(),
+            Some(ConfigsError::DeployedFilesDiverged(paths))
+                if paths == &vec![remote.binding.external_path.to_path_buf()]
+        ));
+        assert_eq!(get_head(&remote.local_path).unwrap(), head_before);
+        assert_eq!(
+            fs::read_to_string(&remote.binding.external_path).unwrap(),
+            "set paste"
+        );
+    }
+
+    #[test]
+    fn syncing_pushes_local_changes() {
+        let temp_dir = TempDir::new("sync-push");
+        let remote = create_remote(temp_dir.path());
+        fs::write(remote.local_path.join("vim/notes"), "todo").unwrap();
+
+        let report =
+            sync_selected_configs(&remote.local_path, &[remote.selected], "origin", "main")
+                .unwrap();
+
+        assert!(report.committed);
+        assert_eq!(
+            get_head(&remote.remote_path).unwrap(),
+            get_head(&remote.local_path).unwrap()
+        );
+        assert!(run_git(&remote.local_path, &["status", "--porcelain"])
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,199 @@
+use std::{
+    collections::BTreeSet,
+    mem,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use notify::{RecursiveMode, Watcher};
+use versions::Version;
+
+use crate::{
+    common::{
+        colors_helper::Colorized, constants, signals_helper::SignalGuard,
+        versions_helper::get_module_path,
+    },
+    types::{bindings::Binding, conflicts::ConflictPolicy},
+};
+
+use super::{
+    bindings_handler::read_version_bindings, eject_handler::is_ejected,
+    repository_handler::get_current_repository, workspace_handler::link_binding,
+};
+
+pub fn watch_deployments(log: impl Fn(String)) -> Result<()> {
+    let guard = SignalGuard::new()?;
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    let mut watched: BTreeSet<PathBuf> = BTreeSet::new();
+
+    let refresh_interval = Duration::from_millis(constants::WATCH_REFRESH_MILLIS);
+    let mut debouncer = Debouncer::new(Duration::from_millis(constants::WATCH_DEBOUNCE_MILLIS));
+    let mut last_refresh: Option<Instant> = None;
+    while !guard.interrupted() {
+        if last_refresh.is_none_or(|el| el.elapsed() >= refresh_interval) {
+            for version in get_active_versions()? {
+                let module_path = get_module_path(&version);
+                if watched.insert(module_path.to_path_buf()) {
+                    watcher.watch(&module_path, RecursiveMode::Recursive)?;
+                    log(format!("Watching {}", module_path.colorize(true)));
+                }
+            }
+            last_refresh = Some(Instant::now());
+        }
+
+        match receiver.recv_timeout(Duration::from_millis(100)) {
+            Ok(Ok(event)) => debouncer.push(event.paths, Instant::now()),
+            Ok(Err(error)) => log(format!("Watch error: {}", error)),
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+        let Some(pending) = debouncer.take_ready(Instant::now()) else {
+            continue;
+        };
+
+        // A select swaps the content of a module directory, so deploy with
+        // what is selected right now.
+        for version in &get_active_versions()? {
+            let module_path = get_module_path(version);
+            let bindings = read_version_bindings(version)?.entries;
+            for binding in find_changed_bindings(&bindings, &module_path, &pending) {
+                match link_binding(&binding, &module_path, ConflictPolicy::Overwrite) {
+                    Ok(()) => log(format!(
+                        "Redeployed {}",
+                        binding.external_path.colorize(false)
+                    )),
+                    Err(error) => log(format!(
+                        "Failed to redeploy {}: {}",
+                        binding.external_path.colorize(false),
+                        error
+                    )),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+struct Debouncer {
+    delay: Duration,
+    pending: BTreeSet<PathBuf>,
+    last_event: Option<Instant>,
+}
+
+impl Debouncer {
+    fn new(delay: Duration) -> Debouncer {
+        Debouncer {
+            delay,
+            pending: BTreeSet::new(),
+            last_event: None,
+        }
+    }
+
+    fn push(&mut self, paths: Vec<PathBuf>, now: Instant) {
+        self.pending.extend(paths);
+        self.last_event = Some(now);
+    }
+
+    fn take_ready(&mut self, now: Instant) -> Option<BTreeSet<PathBuf>> {
+        let last_event = self.last_event?;
+        if self.pending.is_empty() || now.duration_since(last_event) < self.delay {
+            return None;
+        }
+        self.last_event = None;
+        Some(mem::take(&mut self.pending))
+    }
+}
+
+fn find_changed_bindings(
+    bindings: &[Binding],
+    module_path: &Path,
+    changed_paths: &BTreeSet<PathBuf>,
+) -> Vec<Binding> {
+    bindings
+        .iter()
+        .filter(|binding| !binding.deployment.is_symlink())
+        .filter(|binding| {
+            let internal_path = module_path.join(&binding.internal_path);
+            changed_paths
+                .iter()
+                .any(|el| el.starts_with(&internal_path))
+        })
+        .cloned()
+        .collect()
+}
+
+fn get_active_versions() -> Result<Vec<Version>> {
+    let repository = get_current_repository()?;
+    Ok(repository
+        .list_modules()?
+        .into_iter()
+        .filter(|module| !is_ejected(&repository.root_path, &module.name).unwrap_or(false))
+        .filter_map(|module| module.current_version)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::bindings::Deployment;
+
+    use super::*;
+
+    #[test]
+    fn debouncing_waits_for_events_to_settle() {
+        let start = Instant::now();
+        let mut debouncer = Debouncer::new(Duration::from_millis(300));
+        assert_eq!(debouncer.take_ready(start), None);
+
+        debouncer.push(vec![PathBuf::from("/module/a")], start);
+        debouncer.push(
+            vec![PathBuf::from("/module/b")],
+            start + Duration::from_millis(200),
+        );
+        assert_eq!(
+            debouncer.take_ready(start + Duration::from_millis(400)),
+            None
+        );
+
+        assert_eq!(
+            debouncer.take_ready(start + Duration::from_millis(500)),
+            Some(BTreeSet::from([
+                PathBuf::from("/module/a"),
+                PathBuf::from("/module/b")
+            ]))
+        );
+        assert_eq!(debouncer.take_ready(start + Duration::from_secs(1)), None);
+    }
+
+    #[test]
+    fn changed_paths_map_to_deployed_bindings() {
+        let binding = |internal_path: &str, deployment: Deployment| Binding {
+            internal_path: PathBuf::from(internal_path),
+            external_path: PathBuf::from("/home/user").join(internal_path),
+            deployment,
+            ..Default::default()
+        };
+        let bindings = vec![
+            binding("nvim", Deployment::Copy),
+            binding("gitconfig", Deployment::Template),
+            binding("zshrc", Deployment::Symlink),
+            binding("tmux.conf", Deployment::Copy),
+        ];
+        let changed_paths = BTreeSet::from([
+            PathBuf::from("/module/nvim/init.lua"),
+            PathBuf::from("/module/gitconfig"),
+            PathBuf::from("/module/zshrc"),
+            PathBuf::from("/module/tmux.conf.swp"),
+        ]);
+
+        let changed = find_changed_bindings(&bindings, Path::new("/module"), &changed_paths);
+
+        assert_eq!(
+            changed,
+            vec![bindings[0].to_owned(), bindings[1].to_owned()]
+        );
+    }
+}
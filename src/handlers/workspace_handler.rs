@@ -1,31 +1,80 @@
-use std::{fs, path::Path};
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+    process,
+};
 
 use anyhow::Result;
-use commons::utils::file_util::copy;
+use chrono::Local;
+use globset::GlobBuilder;
 use itertools::Itertools;
-use symlink::{remove_symlink_auto, symlink_auto};
 use versions::Version;
+use walkdir::WalkDir;
 
 use crate::{
-    common::versions_helper::get_module_path,
+    common::{
+        files_helper::{get_mode, is_copy_of, set_mode},
+        ignore_helper::IgnoreRules,
+        paths_helper::{convert_to_external_path, convert_to_internal_path},
+        privileges_helper::Privileges,
+        state_helper::get_state_dir,
+        template_helper::render_file,
+        versions_helper::get_module_path,
+    },
     types::{
-        bindings::{Binding, VersionBindings},
+        bindings::{Binding, Deployment, VersionBindings},
+        conflicts::ConflictPolicy,
         errors::ConfigsError,
+        status::{BindingState, BindingStatus},
     },
 };
 
-use super::bindings_handler::read_version_bindings;
+use super::{
+    backup_handler::create_snapshot, bindings_handler::read_version_bindings,
+    env_handler::get_template_variables,
+};
 
 pub fn unlink_version(version: &Version) -> Result<()> {
     let bindings = read_version_bindings(version)?;
     unlink_bindings(&bindings.entries, version)
 }
 
-pub fn link_version(version: &Version, diff_bindings: Option<VersionBindings>) -> Result<()> {
+pub fn link_version(
+    version: &Version,
+    diff_bindings: Option<VersionBindings>,
+    policy: ConflictPolicy,
+) -> Result<()> {
     let bindings = read_version_bindings(version)?.entries.to_owned();
-    link_bindings(&bindings, version)?;
-
     let diff_bindings = diff_bindings.unwrap_or_default();
+    let module_dir_path = get_module_path(version);
+
+    let policy_for = |binding: &Binding| {
+        let previously_managed = diff_bindings
+            .entries
+            .iter()
+            .any(|el| el.external_path == binding.external_path);
+        if previously_managed {
+            ConflictPolicy::Overwrite
+        } else {
+            policy
+        }
+    };
+
+    let mut conflicts = Vec::new();
+    for binding in &bindings {
+        if policy_for(binding) == ConflictPolicy::Abort {
+            conflicts.extend(find_conflicts(binding, &module_dir_path)?);
+        }
+    }
+    if !conflicts.is_empty() {
+        return Err(ConfigsError::PathConflicts(conflicts).into());
+    }
+
+    for binding in &bindings {
+        link_binding(binding, &module_dir_path, policy_for(binding))?;
+    }
+
     let removed_bindings = diff_bindings
         .entries
         .iter()
@@ -40,8 +89,9 @@ pub fn link_version(version: &Version, diff_bindings: Option<VersionBindings>) -
             .join(&version.module.module_dir)
             .join(&binding.internal_path);
         if external_path.is_symlink() && internal_path.exists() {
-            remove_symlink_auto(&external_path)?;
-            fs::rename(internal_path, external_path)?;
+            let privileges = Privileges::new(binding.privileged)?;
+            privileges.remove(&external_path)?;
+            privileges.rename(&internal_path, &external_path)?;
         }
     }
     Ok(())
@@ -55,15 +105,31 @@ fn unlink_bindings(bindings: &[Binding], version: &Version) -> Result<()> {
     Ok(())
 }
 
-fn link_bindings(bindings: &[Binding], version: &Version) -> Result<()> {
-    let module_dir_path = get_module_path(version);
-    for binding in bindings {
-        link_binding(binding, &module_dir_path)?;
+pub fn find_conflicts(binding: &Binding, module_dir_path: &Path) -> Result<Vec<PathBuf>> {
+    let mut conflicts = Vec::new();
+    for entry in expand_pattern_binding(binding, module_dir_path)? {
+        let internal_path = module_dir_path.join(&entry.internal_path);
+        let external_path = &entry.external_path;
+        let is_occupied = external_path.exists() || external_path.is_symlink();
+        if internal_path.exists() && is_occupied && !is_deployed(&entry, module_dir_path) {
+            conflicts.push(external_path.to_path_buf());
+        }
     }
-    Ok(())
+    Ok(conflicts)
 }
 
-pub fn link_binding(binding: &Binding, module_dir_path: &Path) -> Result<()> {
+pub fn link_binding(
+    binding: &Binding,
+    module_dir_path: &Path,
+    policy: ConflictPolicy,
+) -> Result<()> {
+    if binding.pattern.is_some() {
+        for entry in expand_pattern_binding(binding, module_dir_path)? {
+            link_binding(&entry, module_dir_path, policy)?;
+        }
+        return Ok(());
+    }
+
     let external_path = binding.external_path.to_path_buf();
     let internal_path = module_dir_path.join(&binding.internal_path);
 
@@ -73,22 +139,163 @@ pub fn link_binding(binding: &Binding, module_dir_path: &Path) -> Result<()> {
         return Err(ConfigsError::CannotLink(external_path.to_path_buf()).into());
     }
 
-    if !internal_path.exists() {
-        copy(&external_path, &internal_path)?;
+    let privileges = Privileges::new(binding.privileged)?;
+    if !find_conflicts(binding, module_dir_path)?.is_empty() {
+        match policy {
+            ConflictPolicy::Abort => {
+                return Err(ConfigsError::PathConflicts(vec![external_path]).into());
+            }
+            ConflictPolicy::Backup => backup_external_path(&external_path, &privileges)?,
+            ConflictPolicy::Adopt => {
+                if !external_path.is_symlink() {
+                    create_snapshot("adopt", &[internal_path.to_path_buf()])?;
+                    Privileges::User.remove(&internal_path)?;
+                }
+            }
+            ConflictPolicy::Overwrite => {}
+        }
+    }
+
+    let ignore_rules = IgnoreRules::load(module_dir_path)?;
+    if !internal_path.exists() && !internal_path.is_symlink() {
+        privileges.copy_to_user(&external_path, &internal_path, &ignore_rules)?;
     };
+    if let Some(mode) = binding.mode {
+        set_mode(&internal_path, mode)?;
+    }
+
+    // Ignored entries are not part of the config, so they are moved along
+    // instead of being removed with the external path.
+    let ignored_entries = if internal_path.is_dir() {
+        find_ignored_entries(&external_path, &ignore_rules)?
+    } else {
+        Vec::new()
+    };
+    move_entries(
+        &external_path,
+        &internal_path,
+        &ignored_entries,
+        &privileges,
+    )?;
+    privileges.remove(&external_path)?;
+    match binding.deployment {
+        Deployment::Symlink => privileges.symlink(&internal_path, &external_path)?,
+        Deployment::Copy => {
+            privileges.copy(&internal_path, &external_path, &ignore_rules)?;
+            apply_attributes(binding, &privileges)?;
+            move_entries(
+                &internal_path,
+                &external_path,
+                &ignored_entries,
+                &privileges,
+            )?;
+        }
+        Deployment::Template => {
+            let rendered = render_file(&internal_path, &get_template_variables()?)?;
+            match &privileges {
+                Privileges::User => fs::write(&external_path, rendered)?,
+                Privileges::Elevated(_) => {
+                    let rendered_path = get_state_dir()?.join(format!(
+                        "rendered-{}-{}",
+                        process::id(),
+                        Local::now().timestamp_nanos_opt().unwrap_or_default()
+                    ));
+                    fs::create_dir_all(get_state_dir()?)?;
+                    fs::write(&rendered_path, rendered)?;
+                    let result =
+                        privileges.copy(&rendered_path, &external_path, &IgnoreRules::default());
+                    fs::remove_file(&rendered_path)?;
+                    result?;
+                }
+            }
+            apply_attributes(binding, &privileges)?;
+        }
+    }
+
+    Ok(())
+}
 
-    if external_path.is_file() {
-        fs::remove_file(&external_path)?;
-    } else if external_path.is_dir() {
-        fs::remove_dir_all(&external_path)?;
+fn is_deployed(binding: &Binding, module_dir_path: &Path) -> bool {
+    let external_path = &binding.external_path;
+    let internal_path = module_dir_path.join(&binding.internal_path);
+    match binding.deployment {
+        Deployment::Symlink => fs::read_link(external_path).is_ok_and(|el| el == internal_path),
+        Deployment::Copy => IgnoreRules::load(module_dir_path)
+            .and_then(|ignore_rules| is_copy_of(&internal_path, external_path, &ignore_rules))
+            .unwrap_or(false),
+        Deployment::Template => {
+            let rendered = get_template_variables()
+                .and_then(|variables| render_file(&internal_path, &variables));
+            !external_path.is_symlink()
+                && rendered
+                    .is_ok_and(|el| fs::read_to_string(external_path).is_ok_and(|ext| ext == el))
+        }
     }
+}
 
-    symlink_auto(internal_path, external_path)?;
+fn apply_attributes(binding: &Binding, privileges: &Privileges) -> Result<()> {
+    if let Some(mode) = binding.mode {
+        privileges.set_mode(&binding.external_path, mode)?;
+    }
+    if let Some(owner) = &binding.owner {
+        privileges.set_owner(&binding.external_path, owner)?;
+    }
+    Ok(())
+}
 
+pub fn undeploy_binding(binding: &Binding, module_dir_path: &Path) -> Result<()> {
+    for entry in expand_pattern_binding(binding, module_dir_path)? {
+        if is_deployed(&entry, module_dir_path) {
+            Privileges::new(entry.privileged)?.remove(&entry.external_path)?;
+        }
+    }
     Ok(())
 }
 
+pub fn eject_binding(binding: &Binding, module_dir_path: &Path) -> Result<Vec<PathBuf>> {
+    if binding.pattern.is_some() {
+        let mut ejected = Vec::new();
+        for entry in expand_pattern_binding(binding, module_dir_path)? {
+            ejected.extend(eject_binding(&entry, module_dir_path)?);
+        }
+        return Ok(ejected);
+    }
+    if !binding.deployment.is_symlink() {
+        return Ok(Vec::new());
+    }
+
+    let external_path = &binding.external_path;
+    let internal_path = module_dir_path.join(&binding.internal_path);
+    if !fs::read_link(external_path).is_ok_and(|el| el == internal_path) {
+        return Ok(Vec::new());
+    }
+
+    let privileges = Privileges::new(binding.privileged)?;
+    privileges.remove(external_path)?;
+    privileges.copy(
+        &internal_path,
+        external_path,
+        &IgnoreRules::load(module_dir_path)?,
+    )?;
+    apply_attributes(binding, &privileges)?;
+    Ok(vec![external_path.to_path_buf()])
+}
+
 pub fn unlink_binding(binding: &Binding, module_dir_path: &Path) -> Result<()> {
+    if binding.pattern.is_some() {
+        for entry in expand_pattern_binding(binding, module_dir_path)? {
+            if get_binding_status(&entry, module_dir_path)?.state == BindingState::Linked {
+                unlink_binding(&entry, module_dir_path)?;
+            }
+        }
+        return Ok(());
+    }
+
+    if !binding.deployment.is_symlink() {
+        // Copies and rendered templates already are real files.
+        return Ok(());
+    }
+
     let external_path = binding.external_path.to_path_buf();
     let internal_path = module_dir_path.join(&binding.internal_path);
 
@@ -100,9 +307,349 @@ pub fn unlink_binding(binding: &Binding, module_dir_path: &Path) -> Result<()> {
         return Err(ConfigsError::IncorrectLink(external_path.to_path_buf()).into());
     };
 
-    remove_symlink_auto(&external_path)?;
+    let privileges = Privileges::new(binding.privileged)?;
+    let ignore_rules = IgnoreRules::load(module_dir_path)?;
+    privileges.remove(&external_path)?;
+    privileges.copy(&internal_path, &external_path, &ignore_rules)?;
+    apply_attributes(binding, &privileges)?;
+    let ignored_entries = find_ignored_entries(&internal_path, &ignore_rules)?;
+    move_entries(
+        &internal_path,
+        &external_path,
+        &ignored_entries,
+        &privileges,
+    )
+}
+
+pub fn get_version_status(version: &Version) -> Result<Vec<BindingStatus>> {
+    let module_dir_path = get_module_path(version);
+    let bindings = read_version_bindings(version)?;
+    bindings
+        .entries
+        .iter()
+        .map(|binding| get_binding_status(binding, &module_dir_path))
+        .collect()
+}
 
-    copy(&internal_path, &external_path)?;
+pub fn get_binding_status(binding: &Binding, module_dir_path: &Path) -> Result<BindingStatus> {
+    let external_path = &binding.external_path;
+    let internal_path = module_dir_path.join(&binding.internal_path);
 
+    if binding.pattern.is_some() {
+        return get_pattern_binding_status(binding, module_dir_path);
+    }
+
+    let state = if !internal_path.exists() {
+        BindingState::Missing
+    } else if !binding.deployment.is_symlink() {
+        if is_deployed(binding, module_dir_path) {
+            BindingState::Linked
+        } else if external_path.exists() {
+            BindingState::Outdated
+        } else {
+            BindingState::NotLinked
+        }
+    } else if external_path.is_symlink() {
+        match fs::read_link(external_path) {
+            Ok(target) if target == internal_path => BindingState::Linked,
+            _ => BindingState::Broken,
+        }
+    } else {
+        BindingState::NotLinked
+    };
+    let mode = match state {
+        BindingState::Linked => get_mode(&internal_path),
+        _ => get_mode(external_path),
+    };
+
+    Ok(BindingStatus {
+        binding: binding.to_owned(),
+        state,
+        mode,
+        unmanaged: Vec::new(),
+    })
+}
+
+fn backup_external_path(external_path: &Path, privileges: &Privileges) -> Result<()> {
+    create_snapshot("conflict", &[external_path.to_path_buf()])?;
+    privileges.remove(external_path)
+}
+
+fn get_pattern_binding_status(binding: &Binding, module_dir_path: &Path) -> Result<BindingStatus> {
+    let entries: Vec<BindingStatus> = expand_pattern_binding(binding, module_dir_path)?
+        .iter()
+        .map(|entry| get_binding_status(entry, module_dir_path))
+        .try_collect()?;
+    let (unmanaged, managed): (Vec<_>, Vec<_>) = entries
+        .into_iter()
+        .partition(|entry| entry.state == BindingState::Missing);
+
+    let state = if !module_dir_path.join(&binding.internal_path).exists() {
+        BindingState::Missing
+    } else if managed.iter().any(|el| el.state == BindingState::Broken) {
+        BindingState::Broken
+    } else if managed.iter().all(|el| el.state == BindingState::Linked) {
+        BindingState::Linked
+    } else {
+        BindingState::NotLinked
+    };
+
+    Ok(BindingStatus {
+        binding: binding.to_owned(),
+        state,
+        mode: None,
+        unmanaged: unmanaged
+            .into_iter()
+            .map(|entry| entry.binding.external_path)
+            .collect(),
+    })
+}
+
+pub fn expand_pattern_binding(binding: &Binding, module_dir_path: &Path) -> Result<Vec<Binding>> {
+    let Some(pattern) = &binding.pattern else {
+        return Ok(vec![binding.to_owned()]);
+    };
+    let matcher = GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()?
+        .compile_matcher();
+
+    let ignore_rules = IgnoreRules::load(module_dir_path)?;
+    let mut relative_paths = BTreeSet::new();
+    for path in list_files(&binding.external_path, &ignore_rules)? {
+        if matcher.is_match(&path) {
+            relative_paths.insert(path);
+        }
+    }
+    let internal_root = module_dir_path.join(&binding.internal_path);
+    for path in list_files(&internal_root, &IgnoreRules::default())? {
+        let path = convert_to_external_path(&path)?;
+        if matcher.is_match(&path) && !ignore_rules.is_ignored(&path, false) {
+            relative_paths.insert(path);
+        }
+    }
+
+    relative_paths
+        .into_iter()
+        .map(|path| {
+            Ok(Binding {
+                internal_path: binding.internal_path.join(convert_to_internal_path(&path)?),
+                external_path: binding.external_path.join(&path),
+                pattern: None,
+                ..binding.to_owned()
+            })
+        })
+        .collect()
+}
+
+/// Topmost entries below `root` matched by `ignore_rules`, relative to it.
+fn find_ignored_entries(root: &Path, ignore_rules: &IgnoreRules) -> Result<Vec<PathBuf>> {
+    let mut ignored = Vec::new();
+    if ignore_rules.is_empty() || root.is_symlink() || !root.is_dir() {
+        return Ok(ignored);
+    }
+    let mut entries = WalkDir::new(root).min_depth(1).into_iter();
+    while let Some(entry) = entries.next() {
+        let entry = entry?;
+        let relative_path = entry.path().strip_prefix(root)?;
+        if ignore_rules.is_ignored(relative_path, entry.file_type().is_dir()) {
+            if entry.file_type().is_dir() {
+                entries.skip_current_dir();
+            }
+            ignored.push(relative_path.to_path_buf());
+        }
+    }
+    Ok(ignored)
+}
+
+fn move_entries(
+    from: &Path,
+    to: &Path,
+    relative_paths: &[PathBuf],
+    privileges: &Privileges,
+) -> Result<()> {
+    for relative_path in relative_paths {
+        let source = from.join(relative_path);
+        let target = to.join(relative_path);
+        if !source.exists() && !source.is_symlink() {
+            continue;
+        }
+        privileges.remove(&target)?;
+        privileges.create_dir_all(target.parent().unwrap())?;
+        privileges.rename(&source, &target)?;
+    }
     Ok(())
 }
+
+fn list_files(root: &Path, ignore_rules: &IgnoreRules) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    if !root.is_dir() {
+        return Ok(files);
+    }
+    let entries = WalkDir::new(root)
+        .min_depth(1)
+        .into_iter()
+        .filter_entry(|entry| {
+            let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+            !ignore_rules.is_ignored(relative, entry.file_type().is_dir())
+        });
+    for entry in entries {
+        let entry = entry?;
+        if !entry.file_type().is_dir() {
+            files.push(entry.path().strip_prefix(root)?.to_path_buf());
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::{
+        common::{constants, test_helper::TempDir},
+        handlers::backup_handler::{list_snapshots, restore_snapshot},
+        types::{bindings::Binding, conflicts::ConflictPolicy},
+    };
+
+    use super::*;
+
+    fn find_snapshot_of(path: &Path, operation: &str) -> Option<String> {
+        list_snapshots()
+            .unwrap()
+            .into_iter()
+            .find(|el| el.operation == operation && el.entries.iter().any(|e| e.path == path))
+            .map(|el| el.id)
+    }
+
+    #[test]
+    fn adopting_keeps_a_backup_of_the_stored_file() {
+        let temp_dir = TempDir::new("adopt");
+        let module_dir_path = temp_dir.path().join("module");
+        let external_path = temp_dir.path().join("external");
+        fs::create_dir_all(&module_dir_path).unwrap();
+        fs::write(module_dir_path.join("file"), "stored").unwrap();
+        fs::write(&external_path, "local").unwrap();
+        let binding = Binding {
+            internal_path: PathBuf::from("file"),
+            external_path: external_path.to_path_buf(),
+            ..Default::default()
+        };
+
+        link_binding(&binding, &module_dir_path, ConflictPolicy::Adopt).unwrap();
+
+        let internal_path = module_dir_path.join("file");
+        assert_eq!(fs::read_link(&external_path).unwrap(), internal_path);
+        assert_eq!(fs::read_to_string(&internal_path).unwrap(), "local");
+        let id = find_snapshot_of(&internal_path, "adopt").unwrap();
+        restore_snapshot(&id).unwrap();
+        assert_eq!(fs::read_to_string(&internal_path).unwrap(), "stored");
+    }
+
+    #[test]
+    fn aborting_leaves_conflicting_files_alone() {
+        let temp_dir = TempDir::new("abort");
+        let module_dir_path = temp_dir.path().join("module");
+        let external_path = temp_dir.path().join("external");
+        fs::create_dir_all(&module_dir_path).unwrap();
+        fs::write(module_dir_path.join("file"), "stored").unwrap();
+        fs::write(&external_path, "local").unwrap();
+        let binding = Binding {
+            internal_path: PathBuf::from("file"),
+            external_path: external_path.to_path_buf(),
+            ..Default::default()
+        };
+
+        assert!(link_binding(&binding, &module_dir_path, ConflictPolicy::Abort).is_err());
+        assert_eq!(fs::read_to_string(&external_path).unwrap(), "local");
+        assert_eq!(
+            fs::read_to_string(module_dir_path.join("file")).unwrap(),
+            "stored"
+        );
+    }
+
+    #[test]
+    fn ejecting_replaces_links_with_copies() {
+        let temp_dir = TempDir::new("eject");
+        let module_dir_path = temp_dir.path().join("module");
+        let external_path = temp_dir.path().join("external");
+        let other_path = temp_dir.path().join("other");
+        fs::create_dir_all(&module_dir_path).unwrap();
+        fs::write(module_dir_path.join("file"), "stored").unwrap();
+        fs::write(module_dir_path.join("other"), "stored").unwrap();
+        fs::write(&other_path, "local").unwrap();
+        let binding = Binding {
+            internal_path: PathBuf::from("file"),
+            external_path: external_path.to_path_buf(),
+            ..Default::default()
+        };
+        let other_binding = Binding {
+            internal_path: PathBuf::from("other"),
+            external_path: other_path.to_path_buf(),
+            ..Default::default()
+        };
+        link_binding(&binding, &module_dir_path, ConflictPolicy::Abort).unwrap();
+
+        let ejected = eject_binding(&binding, &module_dir_path).unwrap();
+        assert_eq!(ejected, vec![external_path.to_path_buf()]);
+        assert!(eject_binding(&other_binding, &module_dir_path)
+            .unwrap()
+            .is_empty());
+
+        // The copies outlive the module directory being cleared.
+        fs::remove_dir_all(&module_dir_path).unwrap();
+        assert!(!external_path.is_symlink());
+        assert_eq!(fs::read_to_string(&external_path).unwrap(), "stored");
+        assert_eq!(fs::read_to_string(&other_path).unwrap(), "local");
+    }
+
+    fn create_ignoring_module(temp_dir: &TempDir, deployment: Deployment) -> (PathBuf, Binding) {
+        let module_dir_path = temp_dir.path().join("module");
+        let external_path = temp_dir.path().join("nvim");
+        fs::create_dir_all(&module_dir_path).unwrap();
+        fs::write(module_dir_path.join(constants::IGNORE_FILE), "cache/\n").unwrap();
+        fs::create_dir_all(external_path.join("cache")).unwrap();
+        fs::write(external_path.join("init.lua"), "config").unwrap();
+        fs::write(external_path.join("cache/state"), "runtime").unwrap();
+        let binding = Binding {
+            internal_path: PathBuf::from("nvim"),
+            external_path,
+            deployment,
+            ..Default::default()
+        };
+        (module_dir_path, binding)
+    }
+
+    #[test]
+    fn linking_moves_ignored_entries_into_the_module() {
+        let temp_dir = TempDir::new("ignored-link");
+        let (module_dir_path, binding) = create_ignoring_module(&temp_dir, Deployment::Symlink);
+
+        link_binding(&binding, &module_dir_path, ConflictPolicy::Abort).unwrap();
+
+        let external_path = &binding.external_path;
+        assert!(external_path.is_symlink());
+        assert_eq!(
+            fs::read_to_string(external_path.join("cache/state")).unwrap(),
+            "runtime"
+        );
+        assert!(module_dir_path.join("nvim/init.lua").is_file());
+    }
+
+    #[test]
+    fn copying_keeps_ignored_entries_in_place() {
+        let temp_dir = TempDir::new("ignored-copy");
+        let (module_dir_path, binding) = create_ignoring_module(&temp_dir, Deployment::Copy);
+
+        link_binding(&binding, &module_dir_path, ConflictPolicy::Abort).unwrap();
+
+        let external_path = &binding.external_path;
+        assert!(!external_path.is_symlink());
+        assert_eq!(
+            fs::read_to_string(external_path.join("cache/state")).unwrap(),
+            "runtime"
+        );
+        assert!(module_dir_path.join("nvim/init.lua").is_file());
+        assert!(!module_dir_path.join("nvim/cache").exists());
+    }
+}
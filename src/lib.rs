@@ -1,31 +1,58 @@
 use std::{
-    fs::{self},
-    path::Path,
+    env,
+    os::unix::process::ExitStatusExt,
+    path::{Path, PathBuf},
     str::from_utf8,
 };
 
 use anyhow::Result;
+use chrono::Local;
 use clap::{CommandFactory, Parser};
 use clap_complete::generate;
 use colored::Colorize;
 use common::{
     colors_helper::Colorized,
-    versions_helper::{get_module_path, get_version_from_name},
+    paths_helper::get_display_path,
+    versions_helper::{find_module, find_module_strictly, find_version, find_version_strictly},
 };
-use commons::utils::shell_util::current_shell;
+use commons::utils::{path_util::get_home_dir_path, shell_util::current_shell};
 use handlers::{
-    bindings_handler::{bind_path, is_path_bound, read_version_bindings, unbind_path},
+    backup_handler::{list_snapshots, restore_snapshot},
+    bindings_handler::{find_path_owners, read_version_bindings},
+    bootstrap_handler::bootstrap,
+    bundle_handler::{export_bundle, install_bundle},
     commands_handler::{
-        add_module, add_module_with_version, add_version, remove_module, remove_version,
+        add_module, add_module_with_version, add_version, deselect_module, link_path,
+        remove_module, remove_version, select_config, unlink_path,
     },
+    edit_handler::{edit_config_files, get_editor},
+    eject_handler::{clear_ejected, eject_modules, get_eject_record},
+    env_handler::{get_env_script, set_env, unset_env},
+    exec_handler::exec_with_config,
+    git_handler::{auto_commit, get_current_branch},
+    history_handler::{get_repository_history, record_operation, undo_operations},
+    import_handler::{import_bare_git, import_chezmoi, import_stow},
+    picker_handler::pick_config,
     repository_handler::get_current_repository,
-    workspace_handler::{link_binding, link_version, unlink_binding, unlink_version},
+    scope_handler::{apply_scopes, get_hook_script, get_session},
+    settings_handler::{read_settings, update_settings},
+    sync_handler::sync_repository,
+    watch_handler::watch_deployments,
+    workspace_handler::get_version_status,
 };
+use itertools::Itertools;
 use path_absolutize::Absolutize;
 use types::{
-    bindings::VersionBindings,
-    cli::{Cli, Command},
+    backups::RemovalBackup,
+    bindings::{Binding, Deployment},
+    bootstrap::BootstrapOutcome,
+    cli::{BackupsCommand, Cli, Command, EnvCommand, GitCommand, ImportCommand},
+    conflicts::ConflictPolicy,
     errors::ConfigsError,
+    history::{Operation, Selection},
+    imports::ImportReport,
+    shells::ScriptShell,
+    status::BindingState,
 };
 use versions::VersionsCli;
 
@@ -40,12 +67,82 @@ pub fn execute() -> Result<String> {
         Command::Init => handle_init(),
         Command::Add { module, config } => handle_add(&module, &config),
         Command::Remove { module, config } => handle_remove(&module, &config),
-        Command::Select { module, config } => handle_select(&module, &config),
+        Command::Select {
+            module,
+            config,
+            on_conflict,
+        } => handle_select(&module, &config, on_conflict),
+        Command::Bootstrap { profile } => handle_bootstrap(&profile),
+        Command::Pick => handle_pick(),
+        Command::Eject { module } => handle_eject(&module),
         Command::Deselect => handle_deselect(),
+        Command::Exec {
+            module,
+            config,
+            on_conflict,
+            command,
+        } => handle_exec(&module, &config, on_conflict, &command),
         Command::Current => handle_current(),
         Command::Show => handle_show(),
-        Command::Link { path } => handle_link(&path),
+        Command::Status => handle_status(),
+        Command::Link {
+            path,
+            pattern,
+            deploy,
+            validator,
+            on_conflict,
+        } => handle_link(&path, &pattern, deploy, &validator, on_conflict),
         Command::Unlink { path } => handle_unlink(&path),
+        Command::Backups { command } => match command {
+            BackupsCommand::List => handle_backups_list(),
+        },
+        Command::Restore { id } => handle_restore(&id),
+        Command::History => handle_history(),
+        Command::Undo { count } => handle_undo(count),
+        Command::Sync { remote, branch } => handle_sync(&remote, &branch),
+        Command::Watch => handle_watch(),
+        Command::Env { command, shell } => match command {
+            Some(EnvCommand::Set { key, value }) => handle_env_set(&key, &value),
+            Some(EnvCommand::Unset { key }) => handle_env_unset(&key),
+            None => handle_env(shell),
+        },
+        Command::Git { command } => match command {
+            GitCommand::AutoCommit { enabled } => handle_git_auto_commit(enabled),
+        },
+        Command::Hook { shell } => Ok(get_hook_script(shell)),
+        Command::Apply { quiet } => handle_apply(quiet),
+        Command::Export {
+            module,
+            config,
+            output,
+        } => handle_export(&module, &config, &output),
+        Command::Install {
+            bundle,
+            module,
+            config,
+            allow_validators,
+        } => handle_install(&bundle, &module, &config, allow_validators),
+        Command::Import { command } => match command {
+            ImportCommand::Stow {
+                dir,
+                dotfiles,
+                adopt,
+            } => handle_import_stow(&dir, dotfiles, adopt),
+            ImportCommand::Chezmoi { source_dir, module } => {
+                handle_import_chezmoi(&source_dir, &module)
+            }
+            ImportCommand::BareGit {
+                git_dir,
+                work_tree,
+                mappings,
+            } => handle_import_bare_git(&git_dir, &work_tree, &mappings),
+        },
+        Command::Which { path } => handle_which(&path),
+        Command::Edit {
+            module,
+            path,
+            config,
+        } => handle_edit(&module, &path, &config),
         Command::Completions => handle_completions(),
     }
 }
@@ -82,16 +179,24 @@ fn handle_add(module: &str, config: &Option<String>) -> Result<String> {
         }
     }
 
-    let module_str = module.bold().underline();
-    let config_str = match config {
-        Some(config) => config.bold().underline(),
-        None => repository
-            .get_module(module)?
-            .force_current_version()?
-            .name
-            .bold()
-            .underline(),
+    let config = match config {
+        Some(config) => config.to_string(),
+        None => repository.get_module(module)?.force_current_version()?.name,
     };
+    finish_operation(
+        Operation::Add {
+            module: module.to_string(),
+            config: config.to_string(),
+            new_module: !module_preexisted,
+        },
+        None,
+        None,
+        Vec::new(),
+        None,
+    )?;
+
+    let module_str = module.bold().underline();
+    let config_str = config.bold().underline();
 
     if module_preexisted {
         Ok(format!(
@@ -108,40 +213,93 @@ fn handle_add(module: &str, config: &Option<String>) -> Result<String> {
 
 fn handle_remove(module: &str, config: &Option<String>) -> Result<String> {
     let repository = get_current_repository()?;
-    let module = repository.get_module(module)?;
+    let module = find_module_strictly(&repository, module)?;
 
     if let Some(config) = config {
-        let version = get_version_from_name(config, &module);
-        remove_version(&version)?;
-        Ok(format!(
+        let version = find_version_strictly(&module, config)?;
+        let config = &version.name;
+        let bindings = read_version_bindings(&version)?.entries;
+        let backup = remove_version(&version)?;
+        finish_operation(
+            Operation::Remove {
+                module: module.name.to_string(),
+                config: Some(config.to_string()),
+            },
+            None,
+            None,
+            bindings,
+            backup.snapshot.as_ref().map(|el| el.id.to_string()),
+        )?;
+        let mut result = vec![format!(
             "Removed config {} from module {}.",
             config.bold().underline(),
             module.name.bold().underline()
-        ))
+        )];
+        result.extend(get_removal_backup_lines(&backup));
+        Ok(result.join("\n"))
     } else {
-        remove_module(&module)?;
-        Ok(format!(
+        let backup = remove_module(&module)?;
+        finish_operation(
+            Operation::Remove {
+                module: module.name.to_string(),
+                config: None,
+            },
+            None,
+            None,
+            Vec::new(),
+            backup.snapshot.as_ref().map(|el| el.id.to_string()),
+        )?;
+        let mut result = vec![format!(
             "Removed module {}.",
             module.name.bold().underline()
-        ))
+        )];
+        result.extend(get_removal_backup_lines(&backup));
+        Ok(result.join("\n"))
     }
 }
 
-fn handle_select(module: &str, config: &str) -> Result<String> {
-    let repository = get_current_repository()?;
-
-    let mut repo_module = repository.get_module(module)?;
-    repo_module = repository.select_module(&Some(repo_module))?.unwrap();
-
-    let mut diff_bindings = VersionBindings::default();
-    if let Some(current_version) = &repo_module.current_version {
-        diff_bindings = read_version_bindings(current_version)?;
-        unlink_version(current_version)?;
+fn get_removal_backup_lines(backup: &RemovalBackup) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let Some(snapshot) = &backup.snapshot {
+        lines.push(format!(
+            "Deployed files backed up as {}, restore them with `configs restore {}`.",
+            snapshot.id.bold(),
+            snapshot.id
+        ));
+    }
+    for archive in &backup.archives {
+        lines.push(format!(
+            "Config archived to {}, add it back with `configs install`.",
+            archive.display()
+        ));
     }
+    lines
+}
 
-    let version = repo_module.select_version(config)?;
+fn handle_select(module: &str, config: &str, on_conflict: ConflictPolicy) -> Result<String> {
+    let (module, config) = &resolve_selection(module, config)?;
+    let repository = get_current_repository()?;
+    let previous_module = repository.current_module()?.map(|el| el.name);
+    let previous_version = select_config(&repository, module, config, on_conflict)?;
+    clear_ejected(&repository.root_path, module)?;
 
-    link_version(&version, Some(diff_bindings))?;
+    finish_operation(
+        Operation::Select {
+            module: module.to_string(),
+            config: config.to_string(),
+            previous_module,
+        },
+        previous_version.map(|version| Selection {
+            module: module.to_string(),
+            config: version.name,
+        }),
+        Some(Selection {
+            module: module.to_string(),
+            config: config.to_string(),
+        }),
+        Vec::new(),
+        None,
+    )?;
 
     Ok(format!(
         "Selected module {} with config {}.",
@@ -150,19 +308,174 @@ fn handle_select(module: &str, config: &str) -> Result<String> {
     ))
 }
 
+fn handle_bootstrap(profile: &Option<String>) -> Result<String> {
+    let mut previous_module = get_current_repository()
+        .and_then(|el| el.current_module())
+        .ok()
+        .flatten()
+        .map(|el| el.name);
+    let report = bootstrap(profile)?;
+    let mut lines = Vec::new();
+    if let Some(output) = report.initialized.filter(|el| !el.is_empty()) {
+        lines.push(output);
+    }
+    if let Some(backup) = &report.backup {
+        lines.push(format!(
+            "Backed up {} existing entries as {}, restore them with `configs restore {}`.",
+            backup.entries.len(),
+            backup.id.bold(),
+            backup.id
+        ));
+    }
+
+    let mut selected = 0;
+    for entry in &report.entries {
+        let config = entry.config.to_owned().unwrap_or_default();
+        match &entry.outcome {
+            BootstrapOutcome::Selected { previous } => {
+                selected += 1;
+                finish_operation(
+                    Operation::Select {
+                        module: entry.module.to_string(),
+                        config: config.to_string(),
+                        previous_module: previous_module.replace(entry.module.to_string()),
+                    },
+                    previous.as_ref().map(|previous| Selection {
+                        module: entry.module.to_string(),
+                        config: previous.to_string(),
+                    }),
+                    Some(Selection {
+                        module: entry.module.to_string(),
+                        config: config.to_string(),
+                    }),
+                    Vec::new(),
+                    None,
+                )?;
+                lines.push(format!(
+                    "{} {}/{}",
+                    "Selected".green(),
+                    entry.module.bold(),
+                    config
+                ));
+            }
+            BootstrapOutcome::Ejected => lines.push(format!(
+                "{} {}, it was ejected",
+                "Skipped".yellow(),
+                entry.module.bold()
+            )),
+            BootstrapOutcome::NoConfigs => lines.push(format!(
+                "{} {}, it has no configs",
+                "Skipped".yellow(),
+                entry.module.bold()
+            )),
+            BootstrapOutcome::Failed(error) => lines.push(format!(
+                "{} {}/{}: {}",
+                "Failed".red(),
+                entry.module.bold(),
+                config,
+                error
+            )),
+        }
+    }
+    lines.push(format!(
+        "Bootstrapped {} of {} modules.",
+        selected,
+        report.entries.len()
+    ));
+    Ok(lines.join("\n"))
+}
+
+fn handle_eject(module: &Option<String>) -> Result<String> {
+    let modules = match module {
+        Some(module) => Some(vec![find_module_strictly(
+            &get_current_repository()?,
+            module,
+        )?]),
+        None => None,
+    };
+    let ejected = eject_modules(modules)?;
+    if ejected.is_empty() {
+        return Ok("Nothing to eject.".to_string());
+    }
+    for record in &ejected {
+        finish_operation(
+            Operation::Eject {
+                module: record.module.to_string(),
+                config: record.config.to_string(),
+            },
+            Some(Selection {
+                module: record.module.to_string(),
+                config: record.config.to_string(),
+            }),
+            None,
+            Vec::new(),
+            record.backup.to_owned(),
+        )?;
+    }
+    Ok(ejected
+        .iter()
+        .map(|record| {
+            format!(
+                "Ejected module {} with config {}, {} links replaced by copies.",
+                record.module.bold().underline(),
+                record.config.bold().underline(),
+                record.paths.len()
+            )
+        })
+        .join("\n"))
+}
+
+fn handle_pick() -> Result<String> {
+    match pick_config()? {
+        Some(selection) => handle_select(
+            &selection.module,
+            &selection.config,
+            ConflictPolicy::default(),
+        ),
+        None => Ok("Nothing selected.".to_string()),
+    }
+}
+
+fn handle_exec(
+    module: &str,
+    config: &str,
+    on_conflict: ConflictPolicy,
+    command: &[String],
+) -> Result<String> {
+    let (module, config) = resolve_selection(module, config)?;
+    let status = exec_with_config(&module, &config, on_conflict, command)?;
+    if !status.success() {
+        // A signal is reported the way shells do, as 128 plus its number.
+        let code = status.code().or(status.signal().map(|el| 128 + el));
+        return Err(ConfigsError::CommandFailed(command.join(" "), code).into());
+    }
+    Ok(String::new())
+}
+
+fn resolve_selection(module: &str, config: &str) -> Result<(String, String)> {
+    let module = find_module(&get_current_repository()?, module)?;
+    let version = find_version(&module, config)?;
+    Ok((module.name, version.name))
+}
+
 fn handle_deselect() -> Result<String> {
     let repository = get_current_repository()?;
     if let Some(current_module) = &repository.current_module()? {
-        if let Some(current_version) = &current_module.current_version {
-            let mut new_module = current_module.to_owned();
-            new_module.deselect_version()?;
-            repository.select_module(&None)?;
-
-            unlink_version(current_version)?;
-
-            let dir_path = repository.root_path.join(&current_module.directory);
-            fs::remove_dir_all(&dir_path)?;
-            fs::create_dir_all(&dir_path)?;
+        if let Some(version) = deselect_module(&repository, current_module)? {
+            let selection = Selection {
+                module: current_module.name.to_string(),
+                config: version.name.to_string(),
+            };
+            finish_operation(
+                Operation::Deselect {
+                    module: selection.module.to_string(),
+                    config: selection.config.to_string(),
+                },
+                Some(selection),
+                None,
+                read_version_bindings(&version)?.entries,
+                None,
+            )?;
         }
     }
     Ok("Deselected current module and config.".to_string())
@@ -224,10 +537,15 @@ fn handle_show() -> Result<String> {
             };
             let bindings = read_version_bindings(&version)?;
             for binding in bindings.entries {
+                let pattern = match &binding.pattern {
+                    Some(pattern) => format!(" ({})", pattern.dimmed()),
+                    None => String::new(),
+                };
                 result.push(format!(
-                    "    {} -> {}",
+                    "    {} -> {}{}",
                     binding.internal_path.colorize(true),
-                    binding.external_path.colorize(false)
+                    binding.external_path.colorize(false),
+                    pattern
                 ));
             }
         }
@@ -236,40 +554,535 @@ fn handle_show() -> Result<String> {
     Ok(result.join("\n"))
 }
 
-fn handle_link(path: &Path) -> Result<String> {
+fn handle_status() -> Result<String> {
     let repository = get_current_repository()?;
-    let current_version = repository.force_current_module()?.force_current_version()?;
+    let mut result: Vec<String> = Vec::new();
+
+    for module in &repository.list_modules()? {
+        if let Some(record) = get_eject_record(&repository.root_path, &module.name)? {
+            result.push(format!(
+                "{} {} [{}]",
+                module.name.bold().underline(),
+                record.config.underline(),
+                "ejected".dimmed()
+            ));
+            continue;
+        }
+        let Some(current_version) = &module.current_version else {
+            continue;
+        };
+        result.push(format!(
+            "{} {}",
+            module.name.bold().underline(),
+            current_version.name.underline()
+        ));
+        for status in get_version_status(current_version)? {
+            let state = match status.state {
+                BindingState::Linked => status.state.to_string().green(),
+                BindingState::NotLinked | BindingState::Outdated => {
+                    status.state.to_string().yellow()
+                }
+                BindingState::Broken | BindingState::Missing => status.state.to_string().red(),
+            };
+            let mut line = format!(
+                "    {} [{}]",
+                status.binding.external_path.colorize(false),
+                state
+            );
+            if let Some((recorded, actual)) = status.mode_drift() {
+                line.push_str(&format!(
+                    " {}",
+                    format!("mode {:o}, expected {:o}", actual, recorded).red()
+                ));
+            }
+            result.push(line);
+            for path in &status.unmanaged {
+                result.push(format!("        {} {}", "+".yellow(), path.colorize(false)));
+            }
+        }
+    }
+
+    Ok(result.join("\n"))
+}
+
+fn handle_link(
+    path: &Path,
+    pattern: &Option<String>,
+    deploy: Deployment,
+    validator: &Option<String>,
+    on_conflict: ConflictPolicy,
+) -> Result<String> {
     let path = path.absolutize().unwrap().to_path_buf();
+    let (version, binding) = link_path(&path, pattern, deploy, validator, on_conflict)?;
 
-    let already_bound = is_path_bound(&current_version, &path)?;
-    if already_bound {
-        return Err(ConfigsError::PathAlreadyBound(path).into());
+    let selection = Selection {
+        module: version.module.module_name.to_string(),
+        config: version.name.to_string(),
     };
+    finish_operation(
+        Operation::Link {
+            module: selection.module.to_string(),
+            config: selection.config.to_string(),
+            path: path.to_path_buf(),
+        },
+        Some(selection.to_owned()),
+        Some(selection),
+        vec![binding],
+        None,
+    )?;
 
-    let binding = bind_path(&current_version, &path)?;
-    link_binding(&binding, &get_module_path(&current_version))?;
     Ok(format!("Linked path: {}", &path.to_str().unwrap()))
 }
 
+fn handle_edit(module: &str, path: &str, config: &Option<String>) -> Result<String> {
+    let repository = get_current_repository()?;
+    let module = find_module(&repository, module)?;
+    let version = match config {
+        Some(config) => find_version(&module, config)?,
+        None => module.force_current_version()?,
+    };
+    let report = edit_config_files(&version, path, &get_editor())?;
+    auto_commit(
+        &repository.root_path,
+        &[repository.root_path.join(&module.directory)],
+        &format!("edit {}/{}", module.name, version.name),
+    )?;
+
+    let mut result = vec![format!(
+        "Edited {} files of {}/{}.",
+        report.files.len(),
+        module.name.bold().underline(),
+        version.name.bold().underline()
+    )];
+    for path in &report.redeployed {
+        result.push(format!("Redeployed {}", get_display_path(path).green()));
+    }
+    Ok(result.join("\n"))
+}
+
+fn handle_which(path: &Path) -> Result<String> {
+    let path = path.absolutize().unwrap().to_path_buf();
+    let owners = find_path_owners(&path)?;
+    Ok(owners
+        .iter()
+        .map(|owner| {
+            let pattern = match &owner.binding.pattern {
+                Some(pattern) => format!(" ({})", pattern.dimmed()),
+                None => String::new(),
+            };
+            let state = if owner.active {
+                "active".green()
+            } else {
+                "inactive".dimmed()
+            };
+            format!(
+                "{}/{} {} -> {}{} [{}]",
+                owner.module.bold().underline(),
+                owner.config.underline(),
+                owner.binding.internal_path.colorize(true),
+                owner.binding.external_path.colorize(false),
+                pattern,
+                state
+            )
+        })
+        .join("\n"))
+}
+
 fn handle_unlink(path: &Path) -> Result<String> {
+    let path = path.absolutize().unwrap().to_path_buf();
+    let (version, binding, snapshot) = unlink_path(&path)?;
+
+    let selection = Selection {
+        module: version.module.module_name.to_string(),
+        config: version.name.to_string(),
+    };
+    finish_operation(
+        Operation::Unlink {
+            module: selection.module.to_string(),
+            config: selection.config.to_string(),
+            path: path.to_path_buf(),
+        },
+        Some(selection.to_owned()),
+        Some(selection),
+        vec![binding],
+        snapshot.map(|el| el.id),
+    )?;
+
+    Ok(format!("Unlinked path: {}", &path.to_str().unwrap()))
+}
+
+fn handle_history() -> Result<String> {
+    let history = get_repository_history()?;
+    if history.is_empty() {
+        return Ok("No history.".to_string());
+    }
+    Ok(history
+        .iter()
+        .map(|record| {
+            let operation = if record.undone {
+                record.operation.to_string().strikethrough()
+            } else {
+                record.operation.to_string().normal()
+            };
+            format!(
+                "{} {} {}",
+                format!("{:>4}", record.id).bold(),
+                record.timestamp.dimmed(),
+                operation
+            )
+        })
+        .join("\n"))
+}
+
+fn handle_undo(count: usize) -> Result<String> {
+    let undone = undo_operations(count)?;
+    if undone.is_empty() {
+        return Ok("Nothing to undo.".to_string());
+    }
+    for record in &undone {
+        commit_operation(&record.operation, &format!("undo {}", record.operation))?;
+    }
+    Ok(undone
+        .iter()
+        .map(|record| format!("Undid {}.", record.operation.to_string().bold()))
+        .join("\n"))
+}
+
+fn handle_backups_list() -> Result<String> {
+    let snapshots = list_snapshots()?;
+    if snapshots.is_empty() {
+        return Ok("No backups.".to_string());
+    }
+    Ok(snapshots
+        .iter()
+        .map(|snapshot| {
+            format!(
+                "{} {} {} ({} entries)",
+                snapshot.id.bold(),
+                snapshot.created_at.dimmed(),
+                snapshot.operation,
+                snapshot.entries.len()
+            )
+        })
+        .join("\n"))
+}
+
+fn handle_restore(id: &str) -> Result<String> {
+    let snapshot = restore_snapshot(id)?;
+    Ok(format!(
+        "Restored backup {} ({} entries).",
+        snapshot.id.bold().underline(),
+        snapshot.entries.len()
+    ))
+}
+
+fn handle_git_auto_commit(enabled: bool) -> Result<String> {
+    let repository = get_current_repository()?;
+    update_settings(&repository.root_path, |settings| {
+        settings.git.auto_commit = enabled;
+    })?;
+    if enabled {
+        Ok("Enabled git auto-commit.".to_string())
+    } else {
+        Ok("Disabled git auto-commit.".to_string())
+    }
+}
+
+fn handle_sync(remote: &Option<String>, branch: &Option<String>) -> Result<String> {
+    let repository = get_current_repository()?;
+    if remote.is_some() || branch.is_some() {
+        update_settings(&repository.root_path, |settings| {
+            if remote.is_some() {
+                settings.sync.remote = remote.to_owned();
+            }
+            if branch.is_some() {
+                settings.sync.branch = branch.to_owned();
+            }
+        })?;
+    }
+
+    let settings = read_settings(&repository.root_path)?;
+    let remote = settings
+        .sync
+        .remote
+        .ok_or(ConfigsError::SyncRemoteNotConfigured)?;
+    let branch = match settings.sync.branch {
+        Some(branch) => branch,
+        None => get_current_branch(&repository.root_path)?,
+    };
+
+    let report = sync_repository(&remote, &branch)?;
+    let mut result = vec![format!(
+        "Synced with {} ({}).",
+        remote.bold().underline(),
+        branch
+    )];
+    if report.committed {
+        result.push("Committed local changes.".to_string());
+    }
+    for (label, bindings) in [
+        ("Linked", &report.linked),
+        ("Unlinked", &report.unlinked),
+        ("Relinked", &report.relinked),
+    ] {
+        for binding in bindings {
+            result.push(format!(
+                "{} {}",
+                label,
+                binding.external_path.colorize(false)
+            ));
+        }
+    }
+    Ok(result.join("\n"))
+}
+
+fn handle_watch() -> Result<String> {
+    watch_deployments(|message| {
+        println!(
+            "{} {}",
+            Local::now().format("%H:%M:%S").to_string().dimmed(),
+            message
+        );
+    })?;
+    Ok("Stopped watching.".to_string())
+}
+
+fn handle_env(shell: Option<ScriptShell>) -> Result<String> {
+    let shell = match shell {
+        Some(shell) => shell,
+        None => ScriptShell::try_from(current_shell())?,
+    };
+    get_env_script(shell)
+}
+
+fn handle_env_set(key: &str, value: &str) -> Result<String> {
     let repository = get_current_repository()?;
     let current_version = repository.force_current_module()?.force_current_version()?;
-    let path = path.absolutize().unwrap().to_path_buf();
+    let previous = read_version_bindings(&current_version)?.env.remove(key);
+    set_env(&current_version, key, value)?;
+    let selection = Selection {
+        module: current_version.module.module_name.to_string(),
+        config: current_version.name.to_string(),
+    };
+    finish_operation(
+        Operation::SetEnv {
+            module: selection.module.to_string(),
+            config: selection.config.to_string(),
+            key: key.to_string(),
+            previous,
+        },
+        Some(selection.to_owned()),
+        Some(selection),
+        Vec::new(),
+        None,
+    )?;
+    Ok(format!(
+        "Set {} in config {}.",
+        key.bold(),
+        current_version.name.bold().underline()
+    ))
+}
 
-    let already_bound = is_path_bound(&current_version, &path)?;
-    if !already_bound {
-        return Err(ConfigsError::PathNotBound(path).into());
+fn handle_env_unset(key: &str) -> Result<String> {
+    let repository = get_current_repository()?;
+    let current_version = repository.force_current_module()?.force_current_version()?;
+    let previous = read_version_bindings(&current_version)?.env.remove(key);
+    unset_env(&current_version, key)?;
+    let selection = Selection {
+        module: current_version.module.module_name.to_string(),
+        config: current_version.name.to_string(),
     };
+    finish_operation(
+        Operation::UnsetEnv {
+            module: selection.module.to_string(),
+            config: selection.config.to_string(),
+            key: key.to_string(),
+            previous,
+        },
+        Some(selection.to_owned()),
+        Some(selection),
+        Vec::new(),
+        None,
+    )?;
+    Ok(format!(
+        "Unset {} in config {}.",
+        key.bold(),
+        current_version.name.bold().underline()
+    ))
+}
 
-    let binding = unbind_path(&current_version, &path)?;
-    unlink_binding(&binding, &get_module_path(&current_version))?;
+fn handle_apply(quiet: bool) -> Result<String> {
+    let change = apply_scopes(&get_session(), &env::current_dir()?)?;
+    if quiet {
+        return Ok(String::new());
+    }
+    if change.left.is_empty() && change.entered.is_empty() {
+        return Ok("Selections are up to date.".to_string());
+    }
 
-    let internal_path = binding.internal_path;
-    if internal_path.is_file() {
-        fs::remove_file(&internal_path)?;
-    } else if internal_path.is_dir() {
-        fs::remove_dir_all(&internal_path)?;
+    let mut lines = Vec::new();
+    for frame in &change.left {
+        lines.push(format!(
+            "Left {}, restored previous selections.",
+            get_display_path(&frame.directory).bold()
+        ));
     }
+    for frame in &change.entered {
+        lines.push(format!(
+            "Entered {}, selected {}.",
+            get_display_path(&frame.directory).bold(),
+            frame
+                .selections
+                .iter()
+                .map(|(module, config)| format!("{module}/{config}"))
+                .join(", ")
+        ));
+    }
+    Ok(lines.join("\n"))
+}
 
-    Ok(format!("Unlinked path: {}", &path.to_str().unwrap()))
+fn handle_export(module: &str, config: &Option<String>, output: &Path) -> Result<String> {
+    let module = find_module(&get_current_repository()?, module)?;
+    let version = match config {
+        Some(config) => find_version(&module, config)?,
+        None => module.force_current_version()?,
+    };
+    let output = output.absolutize().unwrap().to_path_buf();
+    export_bundle(&version, &output)?;
+    Ok(format!(
+        "Exported config {} of module {} to {}.",
+        version.name.bold().underline(),
+        module.name.bold().underline(),
+        get_display_path(&output)
+    ))
+}
+
+fn handle_install(
+    bundle: &Path,
+    module: &Option<String>,
+    config: &Option<String>,
+    allow_validators: bool,
+) -> Result<String> {
+    let bundle = bundle.absolutize().unwrap().to_path_buf();
+    let installed = install_bundle(&bundle, module, config, allow_validators)?;
+    finish_operation(
+        Operation::Add {
+            module: installed.module.to_string(),
+            config: installed.config.to_string(),
+            new_module: installed.new_module,
+        },
+        None,
+        None,
+        Vec::new(),
+        None,
+    )?;
+
+    let mut lines = vec![format!(
+        "Installed config {} into module {}.",
+        installed.config.bold().underline(),
+        installed.module.bold().underline()
+    )];
+    for hook in &installed.skipped_hooks {
+        lines.push(format!(
+            "{} hook {} already exists, kept it.",
+            "Skipped".yellow(),
+            hook
+        ));
+    }
+    for (path, validator) in &installed.skipped_validators {
+        lines.push(format!(
+            "{} validator {} of {}, use --allow-validators to keep it.",
+            "Skipped".yellow(),
+            validator,
+            get_display_path(path)
+        ));
+    }
+    Ok(lines.join("\n"))
+}
+
+fn handle_import_stow(dir: &Path, dotfiles: bool, adopt: bool) -> Result<String> {
+    let dir = dir.absolutize().unwrap().to_path_buf();
+    let report = import_stow(&dir, dotfiles, adopt)?;
+    finish_import(&report)
+}
+
+fn handle_import_chezmoi(source_dir: &Path, module: &Option<String>) -> Result<String> {
+    let source_dir = source_dir.absolutize().unwrap().to_path_buf();
+    let report = import_chezmoi(&source_dir, module)?;
+    finish_import(&report)
+}
+
+fn handle_import_bare_git(
+    git_dir: &Path,
+    work_tree: &Option<PathBuf>,
+    mappings: &[String],
+) -> Result<String> {
+    let git_dir = git_dir.absolutize().unwrap().to_path_buf();
+    let work_tree = match work_tree {
+        Some(work_tree) => work_tree.absolutize().unwrap().to_path_buf(),
+        None => get_home_dir_path()?,
+    };
+    let report = import_bare_git(&git_dir, &work_tree, mappings)?;
+    finish_import(&report)
+}
+
+fn finish_import(report: &ImportReport) -> Result<String> {
+    if report.modules.is_empty() {
+        return Ok("Nothing to import.".to_string());
+    }
+
+    let mut lines = Vec::new();
+    for summary in &report.modules {
+        finish_operation(
+            Operation::Add {
+                module: summary.module.to_string(),
+                config: summary.config.to_string(),
+                new_module: true,
+            },
+            None,
+            None,
+            Vec::new(),
+            None,
+        )?;
+        lines.push(format!(
+            "Imported module {} with {} files.",
+            summary.module.bold().underline(),
+            summary.files
+        ));
+    }
+    for path in &report.adopted {
+        lines.push(format!("Adopted {}.", get_display_path(path)));
+    }
+    for path in &report.skipped {
+        lines.push(format!(
+            "{} {} is not a link to the imported files, left as is.",
+            "Skipped".yellow(),
+            get_display_path(path)
+        ));
+    }
+    for attribute in &report.unsupported {
+        lines.push(format!("{} {}", "Unsupported:".yellow(), attribute));
+    }
+    Ok(lines.join("\n"))
+}
+
+fn finish_operation(
+    operation: Operation,
+    before: Option<Selection>,
+    after: Option<Selection>,
+    bindings: Vec<Binding>,
+    backup: Option<String>,
+) -> Result<()> {
+    let record = record_operation(operation, before, after, bindings, backup)?;
+    commit_operation(&record.operation, &record.operation.to_string())
+}
+
+fn commit_operation(operation: &Operation, message: &str) -> Result<()> {
+    let repository = get_current_repository()?;
+    let module_path = repository
+        .get_module(operation.module())
+        .map(|module| repository.root_path.join(&module.directory))
+        .unwrap_or_else(|_| repository.root_path.join(operation.module()));
+    auto_commit(&repository.root_path, &[module_path], message)?;
+    Ok(())
 }
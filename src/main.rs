@@ -1,11 +1,16 @@
 #![warn(clippy::pedantic)]
 
-use configs::execute;
+use configs::{common::constants, execute, types::errors::ConfigsError};
 
 fn main() {
     let output = execute().unwrap_or_else(|e| {
         eprintln!("Error: {e}");
-        std::process::exit(1);
+        let code = match e.downcast_ref::<ConfigsError>() {
+            Some(ConfigsError::CommandFailed(_, Some(code))) => *code,
+            Some(ConfigsError::Interrupted) => constants::INTERRUPTED_EXIT_CODE,
+            _ => 1,
+        };
+        std::process::exit(code);
     });
     if !output.is_empty() {
         println!("{output}");
@@ -0,0 +1,27 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Default, Debug)]
+pub struct RemovalBackup {
+    pub snapshot: Option<Snapshot>,
+    pub archives: Vec<PathBuf>,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub id: String,
+    pub created_at: String,
+    pub operation: String,
+    pub entries: Vec<SnapshotEntry>,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SnapshotEntry {
+    pub path: PathBuf,
+    pub mode: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_target: Option<PathBuf>,
+}
@@ -1,5 +1,9 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
 
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
@@ -15,10 +19,56 @@ pub struct ModuleBindings {
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct VersionBindings {
     pub entries: Vec<Binding>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
 }
 
 #[derive(Clone, Default, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Binding {
     pub internal_path: PathBuf,
     pub external_path: PathBuf,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub privileged: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<Owner>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    #[serde(default, skip_serializing_if = "Deployment::is_symlink")]
+    pub deployment: Deployment,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validator: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct PathOwner {
+    pub module: String,
+    pub config: String,
+    pub binding: Binding,
+    pub active: bool,
+}
+
+#[derive(ValueEnum, Clone, Copy, Default, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Deployment {
+    /// Symlink pointing into the module directory
+    #[default]
+    Symlink,
+    /// Real copy of the stored content
+    Copy,
+    /// Stored file rendered with template variables
+    Template,
+}
+
+impl Deployment {
+    pub fn is_symlink(&self) -> bool {
+        *self == Deployment::Symlink
+    }
+}
+
+#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Owner {
+    pub uid: u32,
+    pub gid: u32,
 }
@@ -0,0 +1,23 @@
+use super::backups::Snapshot;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BootstrapOutcome {
+    Selected { previous: Option<String> },
+    NoConfigs,
+    Ejected,
+    Failed(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct BootstrapEntry {
+    pub module: String,
+    pub config: Option<String>,
+    pub outcome: BootstrapOutcome,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BootstrapReport {
+    pub initialized: Option<String>,
+    pub backup: Option<Snapshot>,
+    pub entries: Vec<BootstrapEntry>,
+}
@@ -0,0 +1,25 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::bindings::Binding;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    pub module: String,
+    pub config: String,
+    pub created_at: String,
+    pub bindings: Vec<Binding>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct InstalledBundle {
+    pub module: String,
+    pub config: String,
+    pub new_module: bool,
+    pub skipped_hooks: Vec<String>,
+    pub skipped_validators: Vec<(PathBuf, String)>,
+}
@@ -1,6 +1,8 @@
 use std::path::PathBuf;
 
-use clap::{Parser, Subcommand};
+use clap::{ArgAction, Parser, Subcommand};
+
+use super::{bindings::Deployment, conflicts::ConflictPolicy, shells::ScriptShell};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about = "Manage configs easily")]
@@ -41,18 +43,64 @@ pub enum Command {
         /// Config name
         #[arg()]
         config: String,
+        /// How to handle existing unmanaged files
+        #[arg(long, value_enum, default_value_t)]
+        on_conflict: ConflictPolicy,
+    },
+    /// Set up the repository on a new machine, selecting a config for every module
+    Bootstrap {
+        /// Settings profile naming the config to select per module
+        #[arg(long)]
+        profile: Option<String>,
+    },
+    /// Replace managed links with real copies and stop managing them
+    Eject {
+        /// Only eject this module
+        #[arg(long)]
+        module: Option<String>,
     },
+    /// Pick module/config interactively
+    Pick,
     /// Deselects current module/config
     Deselect,
+    /// Run a command with a config selected, restoring the previous selection afterwards
+    Exec {
+        /// Module name
+        #[arg()]
+        module: String,
+        /// Config name
+        #[arg()]
+        config: String,
+        /// How to handle existing unmanaged files
+        #[arg(long, value_enum, default_value_t)]
+        on_conflict: ConflictPolicy,
+        /// Command to run, after `--`
+        #[arg(last = true, required = true)]
+        command: Vec<String>,
+    },
     /// Current module and config
     Current,
     /// Show current status (modules, configs, links)
     Show,
+    /// Show link state and permission drift of active configs
+    Status,
     /// Link a path to current config
     Link {
         /// Path to a file or directory
         #[arg()]
         path: PathBuf,
+        /// Glob pattern of files to link, relative to the directory
+        #[arg(long)]
+        pattern: Option<String>,
+        /// How to deploy the content to the path
+        #[arg(long, value_enum, default_value_t)]
+        deploy: Deployment,
+        /// Command checking the file after `configs edit`, given its path
+        #[arg(long)]
+        validator: Option<String>,
+        /// How to handle existing unmanaged files
+        #[arg(long, value_enum, default_value_t)]
+        on_conflict: ConflictPolicy,
     },
     /// Unlink a path from the current config
     Unlink {
@@ -60,6 +108,189 @@ pub enum Command {
         #[arg()]
         path: PathBuf,
     },
+    /// Manage backups of deleted or replaced files
+    Backups {
+        #[clap(subcommand)]
+        command: BackupsCommand,
+    },
+    /// Restore a backup to its original location
+    Restore {
+        /// Backup id
+        #[arg()]
+        id: String,
+    },
+    /// List recorded operations
+    History,
+    /// Reverse the last operations
+    Undo {
+        /// Number of operations to undo
+        #[arg(default_value_t = 1)]
+        count: usize,
+    },
+    /// Configure git integration of the repository
+    Git {
+        #[clap(subcommand)]
+        command: GitCommand,
+    },
+    /// Pull and push the repository, relinking changed bindings
+    Sync {
+        /// Git remote to sync with, remembered for later runs
+        #[arg(long)]
+        remote: Option<String>,
+        /// Branch to sync, defaults to the current one
+        #[arg(long)]
+        branch: Option<String>,
+    },
+    /// Keep copied and templated deployments up to date
+    Watch,
+    /// Print export statements for the environment of active configs
+    Env {
+        #[clap(subcommand)]
+        command: Option<EnvCommand>,
+        /// Shell syntax to print, defaults to the current shell
+        #[arg(long, value_enum)]
+        shell: Option<ScriptShell>,
+    },
+    /// Print a shell hook applying `.configs-select` files on directory change
+    Hook {
+        /// Shell to print the hook for
+        #[arg(value_enum)]
+        shell: ScriptShell,
+    },
+    /// Apply the `.configs-select` files of the working directory
+    Apply {
+        /// Only print errors
+        #[arg(long)]
+        quiet: bool,
+    },
+    /// Pack a config into a bundle which can be installed elsewhere
+    Export {
+        /// Module name
+        #[arg()]
+        module: String,
+        /// Config name, defaults to the current config of the module
+        #[arg()]
+        config: Option<String>,
+        /// Bundle file to write
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+    /// Install a bundle as a new module or config
+    Install {
+        /// Bundle file to install
+        #[arg()]
+        bundle: PathBuf,
+        /// Module to install into, defaults to the exported module
+        #[arg(long)]
+        module: Option<String>,
+        /// Config to create, defaults to the exported config
+        #[arg(long)]
+        config: Option<String>,
+        /// Keep the validator commands of the bundle, which run on edit
+        #[arg(long)]
+        allow_validators: bool,
+    },
+    /// Import dotfiles managed by another tool as modules
+    Import {
+        #[clap(subcommand)]
+        command: ImportCommand,
+    },
+    /// Show which module, config and binding manage a path
+    Which {
+        /// Path to look up
+        #[arg()]
+        path: PathBuf,
+    },
+    /// Open files of a config in $EDITOR and redeploy them afterwards
+    Edit {
+        /// Module name
+        #[arg()]
+        module: String,
+        /// External path or glob of the files to edit
+        #[arg()]
+        path: String,
+        /// Config to edit instead of the current one
+        #[arg(long)]
+        config: Option<String>,
+    },
     /// Generate shell completions
     Completions,
 }
+
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+#[clap(rename_all = "lower_case")]
+pub enum ImportCommand {
+    /// Import every package of a GNU Stow directory
+    Stow {
+        /// Stow directory containing the packages
+        #[arg()]
+        dir: PathBuf,
+        /// Packages use the `dot-` prefix of `stow --dotfiles`
+        #[arg(long)]
+        dotfiles: bool,
+        /// Replace existing Stow symlinks with links to the new modules
+        #[arg(long)]
+        adopt: bool,
+    },
+    /// Import a chezmoi source directory
+    Chezmoi {
+        /// chezmoi source directory
+        #[arg()]
+        source_dir: PathBuf,
+        /// Import everything into a single module
+        #[arg(long)]
+        module: Option<String>,
+    },
+    /// Import the files tracked by a bare git repository
+    #[command(name = "bare-git")]
+    BareGit {
+        /// Git directory of the bare repository
+        #[arg()]
+        git_dir: PathBuf,
+        /// Directory the repository is checked out into, defaults to home
+        #[arg(long)]
+        work_tree: Option<PathBuf>,
+        /// Put paths below PREFIX into MODULE, as PREFIX=MODULE
+        #[arg(long = "map")]
+        mappings: Vec<String>,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+#[clap(rename_all = "lower_case")]
+pub enum EnvCommand {
+    /// Set a variable in the current config
+    Set {
+        /// Variable name
+        #[arg()]
+        key: String,
+        /// Variable value
+        #[arg()]
+        value: String,
+    },
+    /// Remove a variable from the current config
+    Unset {
+        /// Variable name
+        #[arg()]
+        key: String,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+#[clap(rename_all = "lower_case")]
+pub enum GitCommand {
+    /// Commit repository changes after each mutating command
+    #[command(name = "auto-commit")]
+    AutoCommit {
+        /// Whether auto-commit is enabled
+        #[arg(action = ArgAction::Set)]
+        enabled: bool,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+#[clap(rename_all = "lower_case")]
+pub enum BackupsCommand {
+    /// List stored backups
+    List,
+}
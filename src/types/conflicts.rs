@@ -0,0 +1,14 @@
+use clap::ValueEnum;
+
+#[derive(ValueEnum, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Stop without touching anything
+    #[default]
+    Abort,
+    /// Move the existing content into a timestamped backup
+    Backup,
+    /// Import the existing content into the config
+    Adopt,
+    /// Replace the existing content
+    Overwrite,
+}
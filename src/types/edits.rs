@@ -0,0 +1,7 @@
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Default)]
+pub struct EditReport {
+    pub files: Vec<PathBuf>,
+    pub redeployed: Vec<PathBuf>,
+}
@@ -0,0 +1,14 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EjectRecord {
+    pub repository: PathBuf,
+    pub module: String,
+    pub config: String,
+    pub ejected_at: String,
+    pub paths: Vec<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup: Option<String>,
+}
@@ -9,6 +9,40 @@ pub enum ConfigsError {
     PathNotBound(PathBuf),
     IncorrectLink(PathBuf),
     CannotLink(PathBuf),
+    UnsupportedPath(PathBuf),
+    InternalPathTaken(PathBuf),
+    EscalationToolNotFound,
+    PrivilegedOperationFailed(String, Vec<PathBuf>),
+    NotADirectory(PathBuf),
+    InvalidPattern(String),
+    PathConflicts(Vec<PathBuf>),
+    BackupNotFound(String),
+    InvalidBackupId(String),
+    NotUndoable(String, Option<String>),
+    SelectionChanged(String, String),
+    ModuleEjected(String),
+    NotAGitRepository(PathBuf),
+    GitFailed(String, String),
+    DeployedFilesDiverged(Vec<PathBuf>),
+    SyncRemoteNotConfigured,
+    NotAFile(PathBuf),
+    UnknownTemplateVariable(String),
+    HookFailed(String, PathBuf),
+    MissingCommand,
+    CommandFailed(String, Option<i32>),
+    UnsupportedShell(String),
+    InvalidEnvKey(String),
+    NameNotFound(String, String, Vec<String>),
+    AmbiguousName(String, String, Vec<String>),
+    InvalidModuleMapping(String),
+    ConfigAlreadyExists(String, String),
+    UnsupportedBundleVersion(u32),
+    BackupVerificationFailed(PathBuf),
+    NoMatchingFiles(String),
+    ValidationFailed(String, PathBuf),
+    UnsafeBundlePath(PathBuf),
+    InvalidBundleName(String),
+    Interrupted,
 }
 
 impl fmt::Display for ConfigsError {
@@ -21,17 +55,169 @@ impl fmt::Display for ConfigsError {
                 write!(
                     f,
                     "Path {} or its ancestor/descendant is already bound.",
-                    path.to_str().unwrap()
+                    path.display()
                 )
             }
             ConfigsError::PathNotBound(path) => {
-                write!(f, "Path {} is not bound.", path.to_str().unwrap())
+                write!(f, "Path {} is not bound.", path.display())
+            }
+            ConfigsError::InternalPathTaken(path) => {
+                write!(
+                    f,
+                    "Path {} in the module is already used by another binding.",
+                    path.display()
+                )
             }
             ConfigsError::IncorrectLink(path) => {
-                write!(f, "Incorrect link for {}.", path.to_str().unwrap())
+                write!(f, "Incorrect link for {}.", path.display())
             }
             ConfigsError::CannotLink(path) => {
-                write!(f, "Cannot link path {}.", path.to_str().unwrap())
+                write!(f, "Cannot link path {}.", path.display())
+            }
+            ConfigsError::UnsupportedPath(path) => {
+                write!(f, "Path {} is not valid UTF-8.", path.to_string_lossy())
+            }
+            ConfigsError::EscalationToolNotFound => {
+                write!(
+                    f,
+                    "Neither sudo nor doas is available for privileged paths."
+                )
+            }
+            ConfigsError::PrivilegedOperationFailed(program, paths) => {
+                let paths = paths
+                    .iter()
+                    .map(|el| el.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "Privileged {} failed for {}.", program, paths)
+            }
+            ConfigsError::NotADirectory(path) => {
+                write!(f, "Path {} is not a directory.", path.display())
+            }
+            ConfigsError::InvalidPattern(pattern) => {
+                write!(f, "Pattern {} is not a valid glob.", pattern)
+            }
+            ConfigsError::PathConflicts(paths) => {
+                let paths = paths
+                    .iter()
+                    .map(|el| el.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(
+                    f,
+                    "Unmanaged content exists at {}. Use --on-conflict to resolve.",
+                    paths
+                )
+            }
+            ConfigsError::BackupNotFound(id) => write!(f, "Backup {} not found.", id),
+            ConfigsError::InvalidBackupId(id) => write!(f, "Backup id {} is not valid.", id),
+            ConfigsError::NotUndoable(operation, Some(backup)) => write!(
+                f,
+                "Cannot undo {}. Use configs restore {} instead.",
+                operation, backup
+            ),
+            ConfigsError::NotUndoable(operation, None) => {
+                write!(f, "Cannot undo {}.", operation)
+            }
+            ConfigsError::ModuleEjected(module) => write!(
+                f,
+                "Module {} is ejected. Select a config to manage it again.",
+                module
+            ),
+            ConfigsError::SelectionChanged(module, config) => write!(
+                f,
+                "Config {}/{} is no longer selected. Select it first.",
+                module, config
+            ),
+            ConfigsError::NotAGitRepository(path) => {
+                write!(f, "{} is not a git repository.", path.display())
+            }
+            ConfigsError::GitFailed(command, stderr) => {
+                write!(f, "git {} failed: {}", command, stderr)
+            }
+            ConfigsError::DeployedFilesDiverged(paths) => {
+                let paths = paths
+                    .iter()
+                    .map(|el| el.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "Deployed files diverged from the repository: {}.", paths)
+            }
+            ConfigsError::SyncRemoteNotConfigured => {
+                write!(f, "No sync remote configured. Use configs sync --remote.")
+            }
+            ConfigsError::NotAFile(path) => {
+                write!(f, "Path {} is not a file.", path.display())
+            }
+            ConfigsError::UnknownTemplateVariable(name) => {
+                write!(f, "Unknown template variable {}.", name)
+            }
+            ConfigsError::HookFailed(hook, script) => {
+                write!(f, "Hook {} failed: {}.", hook, script.display())
+            }
+            ConfigsError::MissingCommand => write!(f, "No command given."),
+            ConfigsError::CommandFailed(command, Some(code)) => {
+                write!(f, "Command {} exited with status {}.", command, code)
+            }
+            ConfigsError::CommandFailed(command, None) => {
+                write!(f, "Command {} was terminated by a signal.", command)
+            }
+            ConfigsError::InvalidEnvKey(key) => {
+                write!(f, "Environment variable name {:?} is not valid.", key)
+            }
+            ConfigsError::UnsupportedShell(shell) => {
+                write!(f, "Shell {} is not supported.", shell)
+            }
+            ConfigsError::NameNotFound(kind, name, suggestions) => {
+                write!(f, "No {} named {}.", kind, name)?;
+                if !suggestions.is_empty() {
+                    write!(f, " Did you mean {}?", suggestions.join(", "))?;
+                }
+                Ok(())
+            }
+            ConfigsError::ConfigAlreadyExists(module, config) => {
+                write!(f, "Config {} already exists in module {}.", config, module)
+            }
+            ConfigsError::BackupVerificationFailed(path) => {
+                write!(f, "Backup of {} could not be verified.", path.display())
+            }
+            ConfigsError::UnsupportedBundleVersion(version) => {
+                write!(f, "Bundle format version {} is not supported.", version)
+            }
+            ConfigsError::InvalidModuleMapping(mapping) => {
+                write!(
+                    f,
+                    "Invalid module mapping {}, expected PREFIX=MODULE.",
+                    mapping
+                )
+            }
+            ConfigsError::NoMatchingFiles(target) => {
+                write!(f, "No files of the config match {}.", target)
+            }
+            ConfigsError::ValidationFailed(validator, path) => {
+                write!(f, "Validator {} rejected {}.", validator, path.display())
+            }
+            ConfigsError::UnsafeBundlePath(path) => {
+                write!(
+                    f,
+                    "Bundle path {} points outside its target.",
+                    path.display()
+                )
+            }
+            ConfigsError::InvalidBundleName(name) => {
+                write!(f, "Bundle name {:?} is not a plain name.", name)
+            }
+            ConfigsError::Interrupted => {
+                write!(f, "Interrupted after restoring the selected config.")
+            }
+            ConfigsError::AmbiguousName(kind, name, candidates) => {
+                write!(
+                    f,
+                    "Name {} matches several {}s: {}.",
+                    name,
+                    kind,
+                    candidates.join(", ")
+                )
             }
         }
     }
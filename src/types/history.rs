@@ -0,0 +1,160 @@
+use std::{fmt, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::paths_helper::get_display_path;
+
+use super::bindings::Binding;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Selection {
+    pub module: String,
+    pub config: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Operation {
+    Add {
+        module: String,
+        config: String,
+        new_module: bool,
+    },
+    Remove {
+        module: String,
+        config: Option<String>,
+    },
+    Select {
+        module: String,
+        config: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        previous_module: Option<String>,
+    },
+    Deselect {
+        module: String,
+        config: String,
+    },
+    Link {
+        module: String,
+        config: String,
+        path: PathBuf,
+    },
+    Unlink {
+        module: String,
+        config: String,
+        path: PathBuf,
+    },
+    Eject {
+        module: String,
+        config: String,
+    },
+    SetEnv {
+        module: String,
+        config: String,
+        key: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        previous: Option<String>,
+    },
+    UnsetEnv {
+        module: String,
+        config: String,
+        key: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        previous: Option<String>,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    pub id: usize,
+    pub timestamp: String,
+    pub repository: PathBuf,
+    pub operation: Operation,
+    pub before: Option<Selection>,
+    pub after: Option<Selection>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bindings: Vec<Binding>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup: Option<String>,
+    #[serde(default)]
+    pub undone: bool,
+}
+
+impl Operation {
+    pub fn module(&self) -> &str {
+        match self {
+            Operation::Add { module, .. }
+            | Operation::Remove { module, .. }
+            | Operation::Select { module, .. }
+            | Operation::Deselect { module, .. }
+            | Operation::Link { module, .. }
+            | Operation::Unlink { module, .. }
+            | Operation::Eject { module, .. }
+            | Operation::SetEnv { module, .. }
+            | Operation::UnsetEnv { module, .. } => module,
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operation::Add {
+                module,
+                config,
+                new_module: true,
+            } => write!(f, "add module {} with config {}", module, config),
+            Operation::Add { module, config, .. } => {
+                write!(f, "add config {} to {}", config, module)
+            }
+            Operation::Remove {
+                module,
+                config: Some(config),
+            } => write!(f, "remove config {} from {}", config, module),
+            Operation::Remove { module, .. } => write!(f, "remove module {}", module),
+            Operation::Select { module, config, .. } => {
+                write!(f, "select {}/{}", module, config)
+            }
+            Operation::Deselect { module, config } => {
+                write!(f, "deselect {}/{}", module, config)
+            }
+            Operation::Link {
+                module,
+                config,
+                path,
+            } => write!(
+                f,
+                "link {} into {}/{}",
+                get_display_path(path),
+                module,
+                config
+            ),
+            Operation::Unlink {
+                module,
+                config,
+                path,
+            } => write!(
+                f,
+                "unlink {} from {}/{}",
+                get_display_path(path),
+                module,
+                config
+            ),
+            Operation::Eject { module, config } => {
+                write!(f, "eject {}/{}", module, config)
+            }
+            Operation::SetEnv {
+                module,
+                config,
+                key,
+                ..
+            } => write!(f, "set {} in {}/{}", key, module, config),
+            Operation::UnsetEnv {
+                module,
+                config,
+                key,
+                ..
+            } => write!(f, "unset {} in {}/{}", key, module, config),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use std::{fmt, path::PathBuf};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hook {
+    PreSelect,
+    PostSelect,
+    PreDeselect,
+    PostDeselect,
+    PreLink,
+    PostLink,
+    PreUnlink,
+    PostUnlink,
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct HookContext {
+    pub module: String,
+    pub old_config: Option<String>,
+    pub new_config: Option<String>,
+    pub paths: Vec<PathBuf>,
+}
+
+impl fmt::Display for Hook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Hook::PreSelect => write!(f, "pre-select"),
+            Hook::PostSelect => write!(f, "post-select"),
+            Hook::PreDeselect => write!(f, "pre-deselect"),
+            Hook::PostDeselect => write!(f, "post-deselect"),
+            Hook::PreLink => write!(f, "pre-link"),
+            Hook::PostLink => write!(f, "post-link"),
+            Hook::PreUnlink => write!(f, "pre-unlink"),
+            Hook::PostUnlink => write!(f, "post-unlink"),
+        }
+    }
+}
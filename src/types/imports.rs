@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+
+use super::bindings::Deployment;
+
+#[derive(Clone, Debug)]
+pub enum ImportContent {
+    File(PathBuf),
+    Bytes(Vec<u8>),
+    Symlink(PathBuf),
+}
+
+#[derive(Clone, Debug)]
+pub struct ImportedFile {
+    pub external_path: PathBuf,
+    pub content: ImportContent,
+    pub mode: Option<u32>,
+    pub deployment: Deployment,
+}
+
+#[derive(Clone, Debug)]
+pub struct ImportedModule {
+    pub name: String,
+    pub files: Vec<ImportedFile>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ImportedSummary {
+    pub module: String,
+    pub config: String,
+    pub files: usize,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ImportReport {
+    pub modules: Vec<ImportedSummary>,
+    pub adopted: Vec<PathBuf>,
+    pub skipped: Vec<PathBuf>,
+    pub unsupported: Vec<String>,
+}
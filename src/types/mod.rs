@@ -1,3 +1,17 @@
+pub mod backups;
 pub mod bindings;
+pub mod bootstrap;
+pub mod bundles;
 pub mod cli;
+pub mod conflicts;
+pub mod edits;
+pub mod ejects;
 pub mod errors;
+pub mod history;
+pub mod hooks;
+pub mod imports;
+pub mod picker;
+pub mod scopes;
+pub mod settings;
+pub mod shells;
+pub mod status;
@@ -0,0 +1,23 @@
+use super::bindings::Binding;
+
+#[derive(Clone, Debug)]
+pub struct PickerEntry {
+    pub module: String,
+    pub config: String,
+    pub current: bool,
+    pub bindings: Vec<Binding>,
+    pub current_bindings: Option<Vec<Binding>>,
+}
+
+impl PickerEntry {
+    pub fn label(&self) -> String {
+        format!("{}/{}", self.module, self.config)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BindingChange {
+    Added,
+    Removed,
+    Changed,
+}
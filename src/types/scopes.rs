@@ -0,0 +1,18 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ScopeFrame {
+    pub directory: PathBuf,
+    pub repository: PathBuf,
+    pub selections: BTreeMap<String, String>,
+    pub previous: Vec<PreviousSelection>,
+    pub previous_module: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PreviousSelection {
+    pub module: String,
+    pub config: Option<String>,
+}
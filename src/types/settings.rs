@@ -0,0 +1,27 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct Settings {
+    #[serde(default)]
+    pub git: GitSettings,
+    #[serde(default)]
+    pub sync: SyncSettings,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct GitSettings {
+    #[serde(default)]
+    pub auto_commit: bool,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct SyncSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+}
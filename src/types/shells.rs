@@ -0,0 +1,24 @@
+use clap::ValueEnum;
+use clap_complete::Shell;
+
+use super::errors::ConfigsError;
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScriptShell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl TryFrom<Shell> for ScriptShell {
+    type Error = ConfigsError;
+
+    fn try_from(shell: Shell) -> Result<Self, Self::Error> {
+        match shell {
+            Shell::Bash => Ok(ScriptShell::Bash),
+            Shell::Zsh => Ok(ScriptShell::Zsh),
+            Shell::Fish => Ok(ScriptShell::Fish),
+            _ => Err(ConfigsError::UnsupportedShell(shell.to_string())),
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use std::{fmt, path::PathBuf};
+
+use super::bindings::Binding;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BindingState {
+    Linked,
+    NotLinked,
+    Broken,
+    Outdated,
+    Missing,
+}
+
+#[derive(Clone, Debug)]
+pub struct BindingStatus {
+    pub binding: Binding,
+    pub state: BindingState,
+    pub mode: Option<u32>,
+    pub unmanaged: Vec<PathBuf>,
+}
+
+impl BindingStatus {
+    pub fn mode_drift(&self) -> Option<(u32, u32)> {
+        match (self.binding.mode, self.mode) {
+            (Some(recorded), Some(actual)) if recorded != actual => Some((recorded, actual)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for BindingState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BindingState::Linked => write!(f, "linked"),
+            BindingState::NotLinked => write!(f, "not linked"),
+            BindingState::Broken => write!(f, "broken link"),
+            BindingState::Outdated => write!(f, "outdated"),
+            BindingState::Missing => write!(f, "missing"),
+        }
+    }
+}